@@ -6,11 +6,35 @@ use std::fmt;
 pub struct ActFile {
     pub version: String,
     pub name: String,
+    /// Which zone's blocks the act is laid out with, e.g. `EmeraldHillZone`
+    pub zone: String,
     pub entities: Vec<Entity>,
     pub width: usize,
     pub tiles: Vec<Option<(usize, u32)>>,
 }
 
+impl ActFile {
+    pub fn load(path: &str) -> Result<ActFile, String> {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path, e))?
+            .parse::<ActFile>()
+            .map_err(|e| format!("In act {}: {}", path, e))
+    }
+
+    /// The act number, from the start of the name, e.g. 1 for `1 Emerald Hill`
+    pub fn number(&self) -> Option<u32> {
+        self.name.split_whitespace().next()?.parse().ok()
+    }
+
+    /// Where the act after this one is, going by the `Act<number>Data.txt` naming
+    /// next to the given path this act was loaded from
+    pub fn next_act_path(&self, path: &str) -> Option<String> {
+        let next = self.number()? + 1;
+        let dir = std::path::Path::new(path).parent()?;
+        Some(dir.join(format!("Act{}Data.txt", next)).to_string_lossy().into_owned())
+    }
+}
+
 impl fmt::Display for ActFile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "{}", self.version)?;
@@ -19,7 +43,7 @@ impl fmt::Display for ActFile {
             writeln!(f, "{}", entity)?;
         }
         writeln!(f, "E")?;
-        writeln!(f, "NORMAL\nEmeraldHillZone.png\nEmeraldHillZone/Block\nEmeraldHillZone/Background/")?;
+        writeln!(f, "NORMAL\n{0}.png\n{0}/Block\n{0}/Background/", self.zone)?;
 
         let tile_count = self.tiles.iter().copied().filter(Option::is_some).count();
         writeln!(f, "{}", tile_count)?;
@@ -64,12 +88,18 @@ impl FromStr for ActFile {
 
         // TODO: Maybe use the act type?
         assert_eq!(lines.next(), Some("NORMAL"));
-        // TODO: Maybe use the filename for the tileset?
-        assert_eq!(lines.next(), Some("EmeraldHillZone.png"));
-        // TODO: Maybe use the path for the blocks?
-        assert_eq!(lines.next(), Some("EmeraldHillZone/Block"));
-        // TODO: Maybe use the path for the background?
-        assert_eq!(lines.next(), Some("EmeraldHillZone/Background/"));
+        // The zone is named after its tileset, and its blocks and background are kept under that name
+        let zone = lines
+            .next()
+            .and_then(|tileset| tileset.strip_suffix(".png"))
+            .ok_or_else(|| "Expected a png tileset".to_string())?
+            .to_string();
+
+        for expected in [format!("{}/Block", zone), format!("{}/Background/", zone)] {
+            if lines.next() != Some(expected.as_str()) {
+                return Err(format!("Expected {:?}", expected));
+            }
+        }
 
         let tile_count = lines
             .next()
@@ -157,6 +187,7 @@ impl FromStr for ActFile {
         Ok(ActFile {
             version,
             name,
+            zone,
             entities,
             width,
             tiles,
@@ -0,0 +1,346 @@
+use gamefox::asset_mgr::GraphicsHolder;
+use gamefox::camera::Camera;
+use gamefox::physical::Vector2;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{Canvas, RenderTarget};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::game::StateHasher;
+use crate::timing::duration_to_ticks;
+use std::hash::Hasher;
+
+/// What an animation does once it has shown its last frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoopMode {
+    /// Start over from the first frame
+    Loop,
+    /// Stay on the last frame
+    Once,
+    /// Play backwards to the first frame, then forwards again
+    PingPong,
+}
+
+impl FromStr for LoopMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "loop" => Ok(LoopMode::Loop),
+            "once" => Ok(LoopMode::Once),
+            "pingpong" => Ok(LoopMode::PingPong),
+            _ => Err(format!("Invalid loop mode {:?}", s)),
+        }
+    }
+}
+
+/// Everything needed to play back one sprite sheet.
+///
+/// The frames are laid out left to right in the image.
+/// Frame durations are in ticks, and a duration of zero holds that frame forever.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationData {
+    pub image: String,
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub durations: Vec<u32>,
+    /// The point in each frame that gets drawn at the position being rendered at
+    pub origin: (i32, i32),
+    pub loop_mode: LoopMode,
+}
+
+/// The contents of the metadata file next to a sprite sheet.
+///
+/// Each line is a key followed by its values, e.g.
+/// ```text
+/// origin 29 11
+/// frames 4
+/// durations 60 60 80 80
+/// loop pingpong
+/// ```
+/// A line with just two numbers is an origin, which is all the older files contain.
+/// `duration` gives every frame the same length, and `size` overrides the frame size
+/// that would otherwise be worked out from the image.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct AnimationMeta {
+    origin: Option<(i32, i32)>,
+    size: Option<(u32, u32)>,
+    frames: Option<usize>,
+    durations: Option<Vec<Duration>>,
+    loop_mode: Option<LoopMode>,
+}
+
+impl FromStr for AnimationMeta {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut meta = AnimationMeta::default();
+        let mut duration = None;
+
+        for line in s.lines() {
+            let mut parts = line.split(' ').filter(|s| !s.is_empty());
+            let key = match parts.next() {
+                Some(key) => key,
+                None => continue,
+            };
+
+            let values = parts.collect::<Vec<&str>>();
+
+            match (key, values.len()) {
+                ("origin", 2) => meta.origin = Some((parse(values[0])?, parse(values[1])?)),
+                ("size", 2) => meta.size = Some((parse(values[0])?, parse(values[1])?)),
+                ("frames", 1) => meta.frames = Some(parse(values[0])?),
+                ("duration", 1) => duration = Some(Duration::from_millis(parse(values[0])?)),
+                ("durations", _) => {
+                    let durations = values
+                        .iter()
+                        .map(|v| parse(v).map(Duration::from_millis))
+                        .collect::<Result<Vec<Duration>, String>>()?;
+                    meta.durations = Some(durations);
+                }
+                ("loop", 1) => meta.loop_mode = Some(values[0].parse()?),
+                (x, 1) if x.parse::<i32>().is_ok() => {
+                    meta.origin = Some((parse(x)?, parse(values[0])?));
+                }
+                _ => return Err(format!("Invalid animation metadata line {:?}", line)),
+            }
+        }
+
+        if let Some(duration) = duration {
+            if meta.durations.is_some() {
+                return Err("Only one of duration and durations can be given".to_string());
+            }
+            meta.durations = Some(vec![duration; meta.frames.unwrap_or(1)]);
+        }
+
+        if let (Some(frames), Some(durations)) = (meta.frames, &meta.durations) {
+            if frames != durations.len() {
+                return Err(format!("Expected {} frame durations, got {}", frames, durations.len()));
+            }
+        }
+
+        Ok(meta)
+    }
+}
+
+fn parse<T: FromStr>(s: &str) -> Result<T, String> where T::Err: ToString {
+    s.parse::<T>().map_err(|e| format!("Parsing error: {}", e.to_string()))
+}
+
+impl AnimationData {
+    /// Loads the sprite sheet with the given name (e.g. `Tails/Run`) from the assets folder,
+    /// along with its metadata file if it has one.
+    /// Only the image header is read, so this works without any textures loaded.
+    pub fn load(image: &str) -> Result<AnimationData, String> {
+        let assets = Path::new("./assets/");
+
+        let meta = match std::fs::read_to_string(assets.join(image)) {
+            Ok(text) => text.parse::<AnimationMeta>().map_err(|e| format!("In metadata for {}: {}", image, e))?,
+            Err(_) => AnimationMeta::default(),
+        };
+
+        let frames = meta.frames.unwrap_or(1);
+        let durations = match meta.durations {
+            Some(durations) => durations.into_iter().map(duration_to_ticks).collect(),
+            None => vec![0; frames],
+        };
+
+        let (frame_width, frame_height) = match meta.size {
+            Some(size) => size,
+            None => {
+                let (width, height) = image_size(&assets.join(format!("{}.png", image)))?;
+                (width / durations.len() as u32, height)
+            }
+        };
+
+        Ok(AnimationData {
+            image: image.to_string(),
+            frame_width,
+            frame_height,
+            durations,
+            origin: meta.origin.unwrap_or((0, 0)),
+            loop_mode: meta.loop_mode.unwrap_or(LoopMode::Loop),
+        })
+    }
+
+    /// Splits the sheet into the given number of equally long frames,
+    /// keeping the total width of the sheet the same.
+    pub fn set_frames(&mut self, frames: usize, duration: Duration) {
+        let sheet_width = self.frame_width * self.durations.len() as u32;
+        self.frame_width = sheet_width / frames as u32;
+        self.durations = vec![duration_to_ticks(duration); frames];
+    }
+}
+
+/// Reads the width and height out of a PNG file's header
+fn image_size(path: &Path) -> Result<(u32, u32), String> {
+    let mut header = [0; 24];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+
+    if &header[..8] != b"\x89PNG\r\n\x1a\n" || &header[12..16] != b"IHDR" {
+        return Err(format!("{} is not a PNG file", path.display()));
+    }
+
+    let mut width = [0; 4];
+    let mut height = [0; 4];
+    width.copy_from_slice(&header[16..20]);
+    height.copy_from_slice(&header[20..24]);
+
+    Ok((u32::from_be_bytes(width), u32::from_be_bytes(height)))
+}
+
+/// The playback state of one sprite sheet
+#[derive(Clone, Debug)]
+pub struct Animation {
+    data: Arc<AnimationData>,
+    frame: usize,
+    reverse: bool,
+    /// Ticks spent on the current frame, scaled by the playback speed
+    elapsed: f64,
+    speed: f64,
+}
+
+impl Animation {
+    pub fn new(data: Arc<AnimationData>) -> Animation {
+        Animation {
+            data,
+            frame: 0,
+            reverse: false,
+            elapsed: 0.0,
+            speed: 1.0,
+        }
+    }
+
+    pub fn load(image: &str) -> Result<Animation, String> {
+        Ok(Animation::new(Arc::new(AnimationData::load(image)?)))
+    }
+
+    /// Sets how fast the animation plays, relative to the durations it was loaded with
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+
+    /// Jumps straight to the given frame, or the last one if there aren't that many
+    pub fn set_frame(&mut self, frame: usize) {
+        self.frame = frame.min(self.data.durations.len() - 1);
+        self.elapsed = 0.0;
+    }
+
+    /// Goes back to the first frame
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.reverse = false;
+        self.elapsed = 0.0;
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.frame);
+        hasher.write_u8(self.reverse as u8);
+        hasher.write_f64(self.elapsed);
+    }
+
+    /// How many ticks it takes to play every frame once, at normal speed
+    pub fn length(&self) -> u32 {
+        self.data.durations.iter().sum()
+    }
+
+    /// Whether a non-looping animation has reached its last frame
+    pub fn is_finished(&self) -> bool {
+        self.data.loop_mode == LoopMode::Once && self.frame + 1 == self.data.durations.len()
+    }
+
+    /// Advances the animation by one tick
+    pub fn update(&mut self) {
+        self.elapsed += self.speed;
+        loop {
+            let duration = self.data.durations[self.frame];
+            if duration == 0 || self.is_finished() {
+                self.elapsed = 0.0;
+                break;
+            }
+
+            if self.elapsed < duration as f64 {
+                break;
+            }
+
+            self.elapsed -= duration as f64;
+            self.next_frame();
+        }
+    }
+
+    fn next_frame(&mut self) {
+        let count = self.data.durations.len();
+        if count == 1 {
+            return;
+        }
+
+        match self.data.loop_mode {
+            LoopMode::Loop => self.frame = (self.frame + 1) % count,
+            LoopMode::Once => self.frame = (self.frame + 1).min(count - 1),
+            LoopMode::PingPong => {
+                if self.reverse && self.frame == 0 {
+                    self.reverse = false;
+                } else if !self.reverse && self.frame + 1 == count {
+                    self.reverse = true;
+                }
+
+                if self.reverse {
+                    self.frame -= 1;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+    }
+
+    /// Draws the current frame with its origin at the given position,
+    /// mirroring it around the origin if `flip` is set.
+    pub fn render<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera, position: Vector2, flip: bool) -> Result<(), String> {
+        self.render_rotated(canvas, camera, position, flip, 0.0, position)
+    }
+
+    /// Like `render`, but turned clockwise by the given number of degrees around `pivot`
+    pub fn render_rotated<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        camera: &Camera,
+        position: Vector2,
+        flip: bool,
+        angle: f64,
+        pivot: Vector2,
+    ) -> Result<(), String> {
+        let data = &self.data;
+
+        let holder = GraphicsHolder::get();
+        let (_, texture) = holder
+            .get_pair(&data.image)
+            .ok_or_else(|| format!("Missing image {}", data.image))?;
+
+        let src = Rect::new(
+            (self.frame as u32 * data.frame_width) as i32,
+            0,
+            data.frame_width,
+            data.frame_height,
+        );
+
+        let origin_x = if flip { data.frame_width as i32 - data.origin.0 } else { data.origin.0 };
+        let dst = Rect::new(
+            (position.x - camera.position.x) as i32 - origin_x,
+            (position.y - camera.position.y) as i32 - data.origin.1,
+            data.frame_width,
+            data.frame_height,
+        );
+
+        let center = Point::new(
+            (pivot.x - camera.position.x) as i32 - dst.x(),
+            (pivot.y - camera.position.y) as i32 - dst.y(),
+        );
+
+        canvas.copy_ex(texture, src, dst, angle, center, flip, false)
+    }
+}
@@ -0,0 +1,269 @@
+use gamefox::physical::{Physical, Vector2};
+use crate::behavior::{EntityBehavior, EntityContext, GameEvent};
+use crate::entity::Entity;
+use crate::game::StateHasher;
+use crate::player::find_ground_height;
+use sdl2::rect::Rect;
+use std::hash::Hash;
+
+/// How many points destroying a badnik is worth
+pub const BADNIK_SCORE: u32 = 100;
+const EXPLOSION_ANIMATION: &str = "Explosion";
+
+const BEE_IDLE_ANIMATION: &str = "Badnik/BeeIdle";
+const BEE_FIRING_ANIMATION: &str = "Badnik/BeeFiring";
+const BEE_SPEED: f64 = 1.0;
+/// How long the bee flies in one direction before turning around
+const BEE_PATROL_TIME: u32 = 256;
+/// How close the player has to be, left or right of the bee, for it to stop and fire
+const BEE_FIRE_RANGE: f64 = 64.0;
+/// How long the bee hovers in place while firing
+const BEE_FIRE_TIME: u32 = 50;
+/// How far into hovering the projectile actually comes out
+const BEE_FIRE_DELAY: u32 = 25;
+/// How long after firing before the bee fires again
+const BEE_FIRE_COOLDOWN: u32 = 120;
+
+const CRAB_IDLE_ANIMATION: &str = "Badnik/CrabIdle";
+const CRAB_WALK_ANIMATION: &str = "Badnik/CrabWalk";
+const CRAB_SPEED: f64 = 0.5;
+/// How long the crab walks before stopping for a moment
+const CRAB_WALK_TIME: u32 = 128;
+const CRAB_PAUSE_TIME: u32 = 60;
+/// How far the ground can step up or down in front of the crab before it counts as an edge
+const CRAB_MAX_STEP: f64 = 8.0;
+/// How wide a stretch of ground gets checked at once, so that small bumps don't stop the crab
+const CRAB_PROBE_RADIUS: f64 = 4.0;
+
+/// Anything that just flies along and hurts the player on contact,
+/// until it leaves the act
+#[derive(Debug, Default)]
+pub struct Enemy;
+
+impl EntityBehavior for Enemy {
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        let out_of_act = |hitbox: Rect| {
+            hitbox.right() < 0 || hitbox.left() > ctx.terrain.pixel_width() as i32 || hitbox.top() > ctx.terrain.pixel_height() as i32
+        };
+        if entity.hitbox().filter(|&hitbox| out_of_act(hitbox)).is_some() {
+            entity.remove();
+        }
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        ctx.events.push(GameEvent::HurtPlayer { source_x: entity.position().x });
+    }
+}
+
+/// Ticks left in the explosion of a destroyed badnik
+#[derive(Debug, Default, Hash)]
+struct Explosion(Option<u32>);
+
+impl Explosion {
+    /// Counts down the explosion, removing the entity once it's done.
+    /// Returns whether the badnik has been destroyed, so it should stop doing anything else.
+    fn update(&mut self, entity: &mut Entity) -> bool {
+        match self.0 {
+            Some(0) => entity.remove(),
+            Some(ticks) => self.0 = Some(ticks - 1),
+            None => return false,
+        }
+        true
+    }
+
+    /// Destroys the badnik if the player is curled up, and otherwise hurts the player
+    fn touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        if self.0.is_some() {
+            return;
+        }
+
+        if ctx.player.is_attacking() {
+            ctx.player.rebound();
+            ctx.player.add_score(BADNIK_SCORE);
+            entity.set_velocity(Vector2 { x: 0.0, y: 0.0 });
+            entity.set_flipped(false);
+            entity.set_animation(EXPLOSION_ANIMATION);
+            self.0 = Some(entity.animation_length());
+        } else {
+            ctx.events.push(GameEvent::HurtPlayer { source_x: entity.position().x });
+        }
+    }
+}
+
+/// Flies back and forth, stopping to fire a `BEEPROJECTILE` down at the player when they get close
+#[derive(Debug, Default)]
+pub struct Bee {
+    explosion: Explosion,
+    facing_right: bool,
+    /// Ticks flown in the current direction
+    patrol_time: u32,
+    /// Ticks spent hovering so far, while firing
+    firing_time: Option<u32>,
+    /// Ticks left before the bee can fire again
+    cooldown: u32,
+}
+
+impl Bee {
+    fn fire(&self, entity: &Entity, ctx: &mut EntityContext) {
+        let position = entity.position();
+        // The stinger is at the back of the sprite, which faces left
+        let x = if self.facing_right { position.x + 2.0 } else { position.x + 32.0 };
+        let mut projectile = match Entity::new(Vector2 { x, y: position.y + 12.0 }, "BEEPROJECTILE".to_string(), Vec::new()) {
+            Ok(projectile) => projectile,
+            Err(e) => {
+                eprintln!("Warning: Could not spawn bee projectile: {}", e);
+                return;
+            }
+        };
+
+        // Projectiles fly the way the bee is facing
+        let velocity = projectile.velocity();
+        let x_speed = if self.facing_right { velocity.x.abs() } else { -velocity.x.abs() };
+        projectile.set_velocity(Vector2 { x: x_speed, y: velocity.y });
+        projectile.set_flipped(self.facing_right);
+        ctx.events.push(GameEvent::Spawn(projectile));
+    }
+}
+
+impl EntityBehavior for Bee {
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        if self.explosion.update(entity) {
+            return;
+        }
+
+        self.cooldown = self.cooldown.saturating_sub(1);
+
+        if let Some(time) = self.firing_time {
+            if time == BEE_FIRE_DELAY {
+                self.fire(entity, ctx);
+            }
+            if time >= BEE_FIRE_TIME {
+                self.firing_time = None;
+                self.cooldown = BEE_FIRE_COOLDOWN;
+                entity.set_animation(BEE_IDLE_ANIMATION);
+            } else {
+                self.firing_time = Some(time + 1);
+            }
+            return;
+        }
+
+        let player = ctx.player.get_position();
+        let center = entity.hitbox().map(|h| h.center().x() as f64).unwrap_or(entity.position().x);
+        let below = player.y > entity.position().y;
+        if self.cooldown == 0 && below && (player.x - center).abs() <= BEE_FIRE_RANGE {
+            self.firing_time = Some(0);
+            entity.set_velocity(Vector2 { x: 0.0, y: 0.0 });
+            entity.set_animation(BEE_FIRING_ANIMATION);
+            return;
+        }
+
+        self.patrol_time += 1;
+        if self.patrol_time >= BEE_PATROL_TIME {
+            self.patrol_time = 0;
+            self.facing_right = !self.facing_right;
+        }
+
+        let speed = if self.facing_right { BEE_SPEED } else { -BEE_SPEED };
+        entity.set_velocity(Vector2 { x: speed, y: 0.0 });
+        entity.set_flipped(self.facing_right);
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        self.explosion.touch(entity, ctx);
+    }
+
+    fn is_destroyed(&self) -> bool {
+        self.explosion.0.is_some()
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.explosion.hash(hasher);
+        self.facing_right.hash(hasher);
+        self.patrol_time.hash(hasher);
+        self.firing_time.hash(hasher);
+        self.cooldown.hash(hasher);
+    }
+}
+
+/// Walks along the terrain, turning around at edges and walls and stopping every so often
+#[derive(Debug, Default)]
+pub struct Crab {
+    explosion: Explosion,
+    facing_right: bool,
+    /// Ticks walked since the last stop
+    walk_time: u32,
+    /// Ticks left standing still
+    pause_left: u32,
+}
+
+impl EntityBehavior for Crab {
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        if self.explosion.update(entity) {
+            return;
+        }
+
+        let hitbox = match entity.hitbox() {
+            Some(hitbox) => hitbox,
+            None => return,
+        };
+        let center = hitbox.center().x() as f64;
+        let feet = hitbox.bottom() as f64;
+        let ground_at = |x: f64| {
+            find_ground_height(Vector2 { x, y: feet }, CRAB_PROBE_RADIUS, 0.0, 0, ctx.terrain).map(|(ground, _angle)| ground as f64)
+        };
+
+        let velocity = entity.velocity();
+        let ground = match ground_at(center) {
+            Some(ground) if ground - feet <= CRAB_MAX_STEP => ground,
+            _ => {
+                // Nothing underneath, so fall until there is, the same way the player does
+                let gravity = ctx.player.physics().gravity;
+                entity.set_velocity(Vector2 { x: 0.0, y: velocity.y + gravity });
+                return;
+            }
+        };
+
+        if self.pause_left > 0 {
+            self.pause_left -= 1;
+            if self.pause_left == 0 {
+                entity.set_animation(CRAB_WALK_ANIMATION);
+            }
+            entity.set_velocity(Vector2 { x: 0.0, y: ground - feet });
+            return;
+        }
+
+        let direction = if self.facing_right { 1.0 } else { -1.0 };
+        let ahead = center + direction * (hitbox.width() as f64 / 2.0 + CRAB_SPEED);
+        let at_edge = ground_at(ahead).filter(|ahead_ground| (ahead_ground - ground).abs() <= CRAB_MAX_STEP).is_none();
+
+        self.walk_time += 1;
+        if at_edge || self.walk_time >= CRAB_WALK_TIME {
+            if at_edge {
+                self.facing_right = !self.facing_right;
+            }
+            self.walk_time = 0;
+            self.pause_left = CRAB_PAUSE_TIME;
+            entity.set_animation(CRAB_IDLE_ANIMATION);
+            entity.set_velocity(Vector2 { x: 0.0, y: ground - feet });
+            return;
+        }
+
+        entity.set_animation(CRAB_WALK_ANIMATION);
+        entity.set_velocity(Vector2 { x: direction * CRAB_SPEED, y: ground - feet });
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        self.explosion.touch(entity, ctx);
+    }
+
+    fn is_destroyed(&self) -> bool {
+        self.explosion.0.is_some()
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.explosion.hash(hasher);
+        self.facing_right.hash(hasher);
+        self.walk_time.hash(hasher);
+        self.pause_left.hash(hasher);
+    }
+}
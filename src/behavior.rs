@@ -0,0 +1,123 @@
+use gamefox::physical::Vector2;
+use crate::badnik::{Bee, Crab, Enemy};
+use crate::block::TerrainGetter;
+use crate::bridge::{self, Bridge};
+use crate::entity::Entity;
+use crate::flags::FlagSpec;
+use crate::game::StateHasher;
+use crate::goal::GoalPost;
+use crate::monitor::{self, Monitor, MonitorIcon};
+use crate::path_switch::{self, PathSwitch};
+use crate::platform::{self, Platform};
+use crate::player::{Player, SolidTop};
+use crate::ring::Ring;
+use crate::spikes::{self, Spikes};
+use crate::spring::{self, Spring};
+use std::fmt;
+
+/// Something that happened to an entity that the rest of the game needs to deal with
+#[derive(Debug)]
+pub enum GameEvent {
+    /// Adds a new entity to the act
+    Spawn(Entity),
+    /// Hits the player from something at the given x position
+    HurtPlayer { source_x: f64 },
+    /// The player has passed the goal post at the given x position, clearing the act
+    ReachGoal { x: f64 },
+}
+
+/// What an entity's behavior gets to look at and change while it runs
+pub struct EntityContext<'a, 'b> {
+    pub terrain: &'a TerrainGetter<'b>,
+    pub player: &'a mut Player,
+    pub events: &'a mut Vec<GameEvent>,
+}
+
+/// What a kind of entity does, on top of playing its animation and moving with its velocity.
+///
+/// Every hook does nothing by default.
+pub trait EntityBehavior: fmt::Debug {
+    /// Runs once, on the first tick the entity is in the game
+    fn on_spawn(&mut self, _entity: &mut Entity, _ctx: &mut EntityContext) {}
+
+    /// Runs every tick, before the entity moves
+    fn update(&mut self, _entity: &mut Entity, _ctx: &mut EntityContext) {}
+
+    /// Runs on every tick that the player's hitbox overlaps the entity's
+    fn on_player_touch(&mut self, _entity: &mut Entity, _ctx: &mut EntityContext) {}
+
+    /// Whether the entity has been destroyed, e.g. a badnik that's exploding
+    fn is_destroyed(&self) -> bool {
+        false
+    }
+
+    /// Whether the player, as they are now, can stand on top of the entity's hitbox
+    fn is_solid(&self, _player: &Player) -> bool {
+        false
+    }
+
+    /// What the player stands on when the entity is solid.
+    /// While one of the entity's own hooks is running, `Entity::top` only gives the hitbox.
+    fn top(&self, entity: &Entity) -> Option<SolidTop> {
+        entity.hitbox_top()
+    }
+
+    /// Where to draw the entity's animation, relative to its position, once for each offset
+    fn sprite_offsets(&self, _entity: &Entity) -> Vec<Vector2> {
+        vec![Vector2 { x: 0.0, y: 0.0 }]
+    }
+
+    /// Adds any state that changes while playing to the game's state hash
+    fn hash_state(&self, _hasher: &mut StateHasher) {}
+}
+
+type CreateBehavior = fn() -> Box<dyn EntityBehavior>;
+
+fn boxed<B: EntityBehavior + Default + 'static>() -> Box<dyn EntityBehavior> {
+    Box::new(B::default())
+}
+
+/// Which behavior goes with each behavior class named after `EA` in `EntityData.txt`
+const BEHAVIORS: &[(&str, CreateBehavior)] = &[
+    ("BEEBADNIK", boxed::<Bee>),
+    ("BRIDGE", boxed::<Bridge>),
+    ("CRABBADNIK", boxed::<Crab>),
+    ("ENEMY", boxed::<Enemy>),
+    ("GOALPOST", boxed::<GoalPost>),
+    ("MONITOR", boxed::<Monitor>),
+    ("MONITORICON", boxed::<MonitorIcon>),
+    ("PATHSWITCH", boxed::<PathSwitch>),
+    ("PLATFORM", boxed::<Platform>),
+    ("RING", boxed::<Ring>),
+    ("SPIKES", boxed::<Spikes>),
+    ("SPRING", boxed::<Spring>),
+];
+
+/// The flags each behavior class takes, in order, with any class not listed taking none
+const FLAG_SCHEMAS: &[(&str, &[FlagSpec])] = &[
+    ("BRIDGE", bridge::FLAGS),
+    ("MONITOR", monitor::FLAGS),
+    ("MONITORICON", monitor::FLAGS),
+    ("PATHSWITCH", path_switch::FLAGS),
+    ("PLATFORM", platform::FLAGS),
+    ("SPIKES", spikes::FLAGS),
+    ("SPRING", spring::FLAGS),
+];
+
+/// The flags that entities with the given behavior class take
+pub fn flag_schema(class: &str) -> &'static [FlagSpec] {
+    FLAG_SCHEMAS
+        .iter()
+        .find(|(name, _)| *name == class)
+        .map(|(_, schema)| *schema)
+        .unwrap_or(&[])
+}
+
+/// Creates the behavior registered for the given class,
+/// or nothing if the class only animates
+pub fn behavior_for(class: &str) -> Option<Box<dyn EntityBehavior>> {
+    BEHAVIORS
+        .iter()
+        .find(|(name, _)| *name == class)
+        .map(|(_, create)| create())
+}
@@ -48,11 +48,14 @@ impl From<RawBlock> for Block {
             } else if layer.name.starts_with("Collision") {
                 collision.push(layer);
             } else {
-                println!("Warning: Ambiguous layer name {}", layer.name);
-                if layer.tiles.iter().any(|t| t.tile >= 340) {
+                eprintln!("Warning: Ambiguous layer name {}", layer.name);
+                // Collision tiles start at 340, so a layer mixing both kinds is neither
+                if layer.tiles.iter().all(|t| t.tile >= 340) {
                     collision.push(layer);
-                } else {
+                } else if layer.tiles.iter().all(|t| t.tile < 340) {
                     graphics.push(layer);
+                } else {
+                    eprintln!("Warning: Skipping layer {}, which mixes graphics and collision tiles", layer.name);
                 }
             }
         }
@@ -75,7 +78,8 @@ impl From<RawBlock> for Block {
     }
 }
 
-type CollisionTile = ([u32; 16], u8);
+/// The height of each column of a 16x16 tile, along with the angle of its surface
+pub type CollisionTile = ([u32; 16], u8);
 
 pub struct TerrainGetter<'a> {
     width: usize,
@@ -99,6 +103,16 @@ impl<'a> TerrainGetter<'a> {
         }
     }
 
+    /// The width of the whole act in pixels
+    pub fn pixel_width(&self) -> u32 {
+        (self.width * BLOCK_PIXEL_LENGTH) as u32
+    }
+
+    /// The height of the whole act in pixels
+    pub fn pixel_height(&self) -> u32 {
+        (self.block_map.len() / self.width * BLOCK_PIXEL_LENGTH) as u32
+    }
+
     pub fn is_occupied(&self, x: i32, y: i32, layer_idx: usize) -> bool {
         if let Some(tile) = self.tile_at(x, y, layer_idx) {
             let pixel_x = x as usize % TILE_PIXEL_LENGTH;
@@ -130,7 +144,8 @@ impl<'a> TerrainGetter<'a> {
             tile_x = BLOCK_TILE_LENGTH - 1 - tile_x;
         }
 
-        let layer = &block.collision_layers[layer_idx];
+        // Blocks with only one collision layer use it for both paths
+        let layer = block.collision_layers.get(layer_idx).unwrap_or(&block.collision_layers[0]);
 
         let tile_entry = &layer.tiles[tile_y * 8 + tile_x];
         
@@ -275,23 +290,36 @@ pub fn flip(surface: &Surface, horiz: bool, vertical: bool) -> Surface<'static>
     dest
 }
 
-pub fn parse_collision_map(map_name: &str) -> Vec<([u32; 16], u8)> {
-    read_collision_map(&GraphicsHolder::get().get_pair(map_name).unwrap().0)
-}
-
-fn read_collision_map(map: &Surface) -> Vec<([u32; 16], u8)> {
-    let mut result = Vec::new();
+/// Parses collision tiles with one tile per line, each made up of
+/// the heights of its 16 columns followed by the angle of its surface
+pub fn parse_collision_tiles(s: &str) -> Result<Vec<CollisionTile>, String> {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(idx, line)| {
+            let values = line
+                .split_whitespace()
+                .map(|v| v.parse::<u32>().map_err(|e| format!("Parsing error in collision tile {}: {}", idx, e)))
+                .collect::<Result<Vec<u32>, String>>()?;
+
+            if values.len() != TILE_PIXEL_LENGTH + 1 {
+                return Err(format!("Expected {} values for collision tile {}, got {}", TILE_PIXEL_LENGTH + 1, idx, values.len()));
+            }
 
-    for y in (0..map.height() / 16).map(|y| y * 16) {
-        for x in (0..map.width() / 16).map(|x| x * 16) {
-            result.push(read_collision_tile(map, Point::new(x as i32, y as i32)));
-            if result.len() == 256 {
-                return result;
+            if values[..TILE_PIXEL_LENGTH].iter().any(|&h| h > TILE_PIXEL_LENGTH as u32) || values[TILE_PIXEL_LENGTH] > 255 {
+                return Err(format!("Collision tile {} is out of range: {:?}", idx, line));
             }
-        }
-    }
 
-    result
+            let mut heights = [0; TILE_PIXEL_LENGTH];
+            heights.copy_from_slice(&values[..TILE_PIXEL_LENGTH]);
+            Ok((heights, values[TILE_PIXEL_LENGTH] as u8))
+        })
+        .collect()
+}
+
+pub fn load_collision_tiles(path: &Path) -> Result<Vec<CollisionTile>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    parse_collision_tiles(&text)
 }
 
 fn get_pixel(map: &Surface, point: Point) -> u32 {
@@ -315,24 +343,3 @@ fn set_pixel(map: &mut Surface, point: Point, color: u32) {
         pixel.copy_from_slice(&result);
     })
 }
-
-fn read_collision_tile(map: &Surface, top_left: Point) -> ([u32; 16], u8) {
-    let masks = map.pixel_format_enum().into_masks().unwrap();
-    let color_mask = masks.rmask | masks.gmask | masks.bmask;
-    let g_shift = masks.gmask.trailing_zeros();
-
-    let mut result = [0; 16];
-    let mut angle = 0;
-    for (col, x) in (top_left.x()..top_left.x() + 16).enumerate() {
-        for y in top_left.y()..top_left.y() + 16 {
-            let color = get_pixel(map, Point::new(x, y));
-            if color & color_mask != 0 {
-                angle = ((color & masks.gmask) >> g_shift) as u8;
-                result[col] = 16 - (y - top_left.y()) as u32;
-                break;
-            }
-        }
-    }
-
-    (result, angle)
-}
\ No newline at end of file
@@ -0,0 +1,124 @@
+use gamefox::physical::{Physical, Vector2};
+use crate::behavior::{EntityBehavior, EntityContext};
+use crate::entity::Entity;
+use crate::flags::{FlagSpec, FlagType};
+use crate::game::StateHasher;
+use crate::player::{Player, SolidTop};
+use std::f64::consts::FRAC_PI_2;
+use std::hash::Hash;
+
+/// How many logs long the bridge is, which is 12 for the ones in Emerald Hill
+pub const FLAGS: &[FlagSpec] = &[FlagSpec { name: "logs", kind: FlagType::Integer { min: 1, max: MAX_LOGS }, default: Some("12") }];
+/// The longest a bridge can be, well past anything that fits on screen
+const MAX_LOGS: i64 = 64;
+/// How much deeper the sag gets for every log between the player and the nearest end of the bridge
+const SAG_PER_LOG: f64 = 2.0;
+/// How many ticks the bridge takes to sag all the way, or to straighten back out
+const SAG_TIME: u32 = 16;
+
+/// A row of logs, as many as the logs flag of its entity, that can be stood on and sags under the player.
+///
+/// The sag is deepest at the log being stood on and curves back up to the ends,
+/// so the player sinks deeper the closer they get to the middle.
+#[derive(Debug, Default)]
+pub struct Bridge {
+    logs: usize,
+    /// The log that was last stood on, which the sag is centered on
+    center_log: usize,
+    /// How far into sagging the bridge is, out of `SAG_TIME`
+    sag_time: u32,
+    /// How far each log is below where it would be without any sag
+    depths: Vec<f64>,
+    /// How far down the top is under the player, and how much that changed during the current tick
+    top_depth: f64,
+    top_moved: f64,
+}
+
+impl Bridge {
+    fn log_width(entity: &Entity) -> f64 {
+        entity.kind().map(|kind| kind.hitbox_size.0 as f64).unwrap_or(16.0)
+    }
+
+    /// Which log is under the given x position, counting anything off the ends as the end log
+    fn log_at(&self, entity: &Entity, x: f64) -> usize {
+        let left = entity.hitbox_top().map(|top| top.left).unwrap_or(entity.position().x);
+        let log = ((x - left) / Bridge::log_width(entity)).floor().max(0.0) as usize;
+        log.min(self.logs - 1)
+    }
+
+    /// Works out how far down each log is, following a quarter sine wave
+    /// from each end of the bridge down to the center log
+    fn update_depths(&mut self) {
+        let center = self.center_log;
+        let from_end = (center + 1).min(self.logs - center) as f64;
+        let deepest = SAG_PER_LOG * from_end * (FRAC_PI_2 * self.sag_time as f64 / SAG_TIME as f64).sin();
+
+        for (log, depth) in self.depths.iter_mut().enumerate() {
+            let along = if log <= center {
+                (log + 1) as f64 / (center + 1) as f64
+            } else {
+                (self.logs - log) as f64 / (self.logs - center) as f64
+            };
+            *depth = deepest * (FRAC_PI_2 * along).sin();
+        }
+    }
+}
+
+impl EntityBehavior for Bridge {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        self.logs = entity.flags().integer("logs") as usize;
+        self.depths = vec![0.0; self.logs];
+    }
+
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        let stood_on = self.top(entity).filter(|top| ctx.player.is_standing_on(top)).is_some();
+        let player_log = self.log_at(entity, ctx.player.get_position().x);
+
+        if stood_on {
+            self.center_log = player_log;
+            self.sag_time = (self.sag_time + 1).min(SAG_TIME);
+        } else {
+            self.sag_time = self.sag_time.saturating_sub(1);
+        }
+        self.update_depths();
+
+        // The top follows the sag under the player, so anyone standing on it gets carried down with it
+        let depth = self.depths[player_log];
+        self.top_moved = depth - self.top_depth;
+        self.top_depth = depth;
+    }
+
+    fn is_solid(&self, _player: &Player) -> bool {
+        true
+    }
+
+    fn top(&self, entity: &Entity) -> Option<SolidTop> {
+        let top = entity.hitbox_top()?;
+        Some(SolidTop {
+            left: top.left,
+            right: top.left + Bridge::log_width(entity) * self.logs as f64,
+            y: top.y + self.top_depth,
+            velocity: Vector2 { x: 0.0, y: self.top_moved },
+        })
+    }
+
+    fn sprite_offsets(&self, entity: &Entity) -> Vec<Vector2> {
+        let width = Bridge::log_width(entity);
+        self.depths
+            .iter()
+            .enumerate()
+            .map(|(log, depth)| Vector2 { x: log as f64 * width, y: *depth })
+            .collect()
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.logs.hash(hasher);
+        self.center_log.hash(hasher);
+        self.sag_time.hash(hasher);
+        for depth in self.depths.iter() {
+            hasher.write_f64(*depth);
+        }
+        hasher.write_f64(self.top_depth);
+        hasher.write_f64(self.top_moved);
+    }
+}
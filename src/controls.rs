@@ -0,0 +1,78 @@
+use gamefox::input;
+use sdl2::keyboard::Keycode;
+use std::ops::BitOr;
+
+/// A set of the buttons the game uses
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Buttons(u8);
+
+impl Buttons {
+    pub const NONE: Buttons = Buttons(0);
+    pub const LEFT: Buttons = Buttons(1 << 0);
+    pub const RIGHT: Buttons = Buttons(1 << 1);
+    pub const UP: Buttons = Buttons(1 << 2);
+    pub const DOWN: Buttons = Buttons(1 << 3);
+    pub const JUMP: Buttons = Buttons(1 << 4);
+
+    pub fn from_bits(bits: u8) -> Buttons {
+        Buttons(bits)
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, other: Buttons) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Buttons {
+    type Output = Buttons;
+
+    fn bitor(self, rhs: Buttons) -> Buttons {
+        Buttons(self.0 | rhs.0)
+    }
+}
+
+/// Which keys on the keyboard map to which buttons
+const KEY_MAP: &[(Keycode, Buttons)] = &[
+    (Keycode::A, Buttons::LEFT),
+    (Keycode::D, Buttons::RIGHT),
+    (Keycode::W, Buttons::UP),
+    (Keycode::S, Buttons::DOWN),
+    (Keycode::Space, Buttons::JUMP),
+];
+
+/// Reads which buttons are currently held down on the keyboard.
+/// A key that was pressed and released again since the last update still counts,
+/// so that quick taps aren't lost between ticks.
+pub fn read_keyboard() -> Buttons {
+    KEY_MAP
+        .iter()
+        .filter(|(key, _)| input::key_held(*key) || input::key_pressed(*key))
+        .fold(Buttons::NONE, |buttons, (_, button)| buttons | *button)
+}
+
+/// The buttons held on one tick, along with the ones held on the tick before
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Input {
+    held: Buttons,
+    previous: Buttons,
+}
+
+impl Input {
+    /// Moves on to the next tick, with the given buttons held down
+    pub fn next(self, held: Buttons) -> Input {
+        Input { held, previous: self.held }
+    }
+
+    pub fn held(self, button: Buttons) -> bool {
+        self.held.contains(button)
+    }
+
+    /// Whether the button went down on this tick
+    pub fn pressed(self, button: Buttons) -> bool {
+        self.held.contains(button) && !self.previous.contains(button)
+    }
+}
@@ -1,86 +1,468 @@
 use gamefox::physical::Vector2;
 use gamefox::renderable::Renderable;
 use gamefox::camera::Camera;
-use gamefox::animation::Animation;
+use crate::animation::{Animation, AnimationData};
+use crate::behavior::{behavior_for, flag_schema, EntityBehavior, EntityContext};
+use crate::flags::EntityFlags;
 use std::str::FromStr;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
 use sdl2::render::{RenderTarget, Canvas};
 use lazy_static::lazy_static;
-use std::collections::HashMap;
+use crate::game::StateHasher;
+use crate::player::{Player, SolidTop};
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
 use std::time::Duration;
-use std::sync::RwLock;
+use std::sync::Arc;
 use std::fmt;
 
 lazy_static! {
-    static ref ENTITY_DATA: RwLock<HashMap<String, Vec<Animation>>> = {
-        let data = std::fs::read_to_string("./assets/EntityData.txt").unwrap();
-        let mut result = HashMap::new();
-
-        for line in data.lines() {
-            let mut parts = line.split(' ').filter(|s| !s.is_empty());
-            assert_eq!(parts.next(), Some("OBJ"));
-            let kind = parts.next().unwrap().to_string();
-            let mut parts = parts.skip(7);
-
-            let mut anims = Vec::new();
-            let mut n = parts.next().unwrap();
-            while n != "EA" {
-                // Trim off the .png extention
-                let image = n[..n.len() - 4].to_string();
-
-                let duration = parts.next().unwrap().parse::<i32>().unwrap();
-                let duration = if duration == -1 {
-                    Duration::new(0, 0)
-                } else {
-                    Duration::from_millis(duration as u64)
-                };
+    /// Every kind of entity in `EntityData.txt`, by name.
+    /// Definitions that don't parse are left out, so entities of that kind load as placeholders.
+    static ref ENTITY_DATA: BTreeMap<String, EntityKind> = {
+        let data = std::fs::read_to_string("./assets/EntityData.txt").unwrap_or_else(|e| {
+            eprintln!("Warning: Could not read EntityData.txt: {}", e);
+            String::new()
+        });
+
+        data.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match line.parse::<EntityKind>() {
+                Ok(kind) => Some((kind.name.clone(), kind)),
+                Err(e) => {
+                    eprintln!("Warning: Skipping invalid entity definition {:?}: {}", line, e);
+                    None
+                }
+            })
+            .collect()
+    };
+}
+
+/// How big the marker drawn in place of an entity of a missing kind is
+const PLACEHOLDER_SIZE: u32 = 16;
+const PLACEHOLDER_COLOR: (u8, u8, u8) = (255, 0, 255);
 
-                let frames = parts.next().unwrap().parse::<usize>().unwrap();
+/// Old names for kinds of entity that still show up in some act files
+const KIND_ALIASES: &[(&str, &str)] = &[("SPRING_YELLOW", "SPRINGYELLOW")];
 
-                anims.push(Animation::new(image, frames, duration));
+/// Turns an old name for a kind of entity into the one used in `EntityData.txt`
+fn canonical_kind(name: String) -> String {
+    match KIND_ALIASES.iter().find(|(alias, _)| *alias == name) {
+        Some((_, kind)) => kind.to_string(),
+        None => name,
+    }
+}
+
+/// Checks flags against the schema of the kind's behavior class,
+/// leaving them unchecked if the kind doesn't exist
+fn parse_flags<S: AsRef<str>>(kind: &str, flags: &[S]) -> Result<EntityFlags, String> {
+    match entity_kind(kind) {
+        Some(definition) => EntityFlags::parse(flag_schema(&definition.behavior), flags)
+            .map_err(|e| format!("In {} entity: {}", kind, e)),
+        None => Ok(EntityFlags::unchecked(flags)),
+    }
+}
+
+/// Looks up the definition of a kind of entity
+pub fn entity_kind(name: &str) -> Option<&'static EntityKind> {
+    ENTITY_DATA.get(name)
+}
+
+/// The definition of one kind of entity, from a line of `EntityData.txt` like
+/// ```text
+/// OBJ RING 0 0 16 16 0 0 0 Ring.png 133 4 RingSparkle.png 75 4 EA RING ENDOBJ
+/// ```
+/// which is the name, the hitbox offset and size, the starting velocity, a number that's always 0 and goes unused,
+/// then each animation's image, frame duration in milliseconds (-1 to never change frames)
+/// and frame count, and finally the behavior class after `EA`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntityKind {
+    pub name: String,
+    /// Where the hitbox starts, relative to the entity's position
+    pub hitbox_offset: (i32, i32),
+    pub hitbox_size: (u32, u32),
+    pub velocity: Vector2,
+    /// Played by each entity of this kind, with the first one playing to begin with
+    pub animations: Vec<Arc<AnimationData>>,
+    /// Which kind of behavior the entity has, e.g. `RING` or `SPRING`, which several kinds can share
+    pub behavior: String,
+}
+
+impl EntityKind {
+    /// Finds the animation that uses the given image, e.g. `RingSparkle`
+    pub fn animation(&self, image: &str) -> Option<usize> {
+        self.animations.iter().position(|data| data.image == image)
+    }
+}
 
-                n = parts.next().unwrap();
+impl FromStr for EntityKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(' ').filter(|s| !s.is_empty());
+        let mut next = |what: &str| parts.next().ok_or_else(|| format!("Expected {}", what));
+
+        if next("OBJ")? != "OBJ" {
+            return Err("Expected OBJ".to_string());
+        }
+
+        let name = next("name")?.to_string();
+        let hitbox_offset = (parse(next("hitbox x")?)?, parse(next("hitbox y")?)?);
+        let hitbox_size = (parse(next("hitbox width")?)?, parse(next("hitbox height")?)?);
+        let velocity = Vector2 { x: parse(next("x velocity")?)?, y: parse(next("y velocity")?)? };
+        parse::<i32>(next("seventh number")?)?;
+
+        let mut animations = Vec::new();
+        loop {
+            let image = next("animation or EA")?;
+            if image == "EA" {
+                break;
             }
 
-            result.insert(kind, anims);
+            let image = image
+                .strip_suffix(".png")
+                .ok_or_else(|| format!("Expected a png image, got {:?}", image))?;
+
+            let duration = match parse::<i32>(next("frame duration")?)? {
+                -1 => Duration::new(0, 0),
+                duration => Duration::from_millis(duration as u64),
+            };
+
+            let frames = parse::<usize>(next("frame count")?)?;
+
+            let mut data = AnimationData::load(image)?;
+            data.set_frames(frames, duration);
+            animations.push(Arc::new(data));
         }
 
-        RwLock::new(result)
-    };
+        let behavior = next("behavior class")?.to_string();
+
+        if next("ENDOBJ")? != "ENDOBJ" {
+            return Err("Expected ENDOBJ".to_string());
+        }
+
+        if let Ok(extra) = next("") {
+            return Err(format!("Trailing data {:?} after ENDOBJ", extra));
+        }
+
+        Ok(EntityKind {
+            name,
+            hitbox_offset,
+            hitbox_size,
+            velocity,
+            animations,
+            behavior,
+        })
+    }
+}
+
+fn parse<T: FromStr>(s: &str) -> Result<T, String> where T::Err: ToString {
+    s.parse::<T>().map_err(|e| format!("Parsing error: {}", e.to_string()))
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Debug)]
 pub struct Entity {
     position: Vector2,
     kind: String,
-    flags: Vec<String>,
+    flags: EntityFlags,
+    /// Where the entity was placed, which it goes back to when it goes dormant
+    spawn_position: Vector2,
+    /// Spawned while playing rather than placed in the act, so it's gone for good once out of range
+    temporary: bool,
+    /// Out of range of the camera, so it doesn't do anything or get drawn
+    dormant: bool,
+
+    velocity: Vector2,
+    /// Clockwise, in degrees, around the middle of the hitbox
+    rotation: f64,
+    /// Mirrored left to right, for facing the other way than the sprite does
+    flipped: bool,
+    animations: Vec<Animation>,
+    /// Which of the animations is playing
+    current_animation: usize,
+
+    /// Taken out while one of its hooks is running, so that it can be given the entity
+    behavior: Option<Box<dyn EntityBehavior>>,
+    spawned: bool,
+    removed: bool,
 }
 
 impl Entity {
-    pub fn update(&mut self) {
-        let mut data = ENTITY_DATA.write().unwrap();
-        let anims = data.get_mut(&self.kind).unwrap();
-        if let Some(anim) = anims.get_mut(0) {
-            anim.update();
-        }
+    /// Creates an entity, which is just a placeholder if its kind doesn't exist.
+    /// Fails if the flags don't fit its kind.
+    pub fn new(position: Vector2, kind: String, flags: Vec<String>) -> Result<Entity, String> {
+        Entity::build(position, kind, &flags)
     }
 
-    pub fn new(position: Vector2, kind: String, flags: Vec<String>) -> Entity {
-        if ENTITY_DATA.read().unwrap().get(&kind).is_none() {
-            panic!("Invalid entity kind {:?}", kind);
+    /// Checks the kind and flags before setting up the entity.
+    /// A kind that doesn't exist only gets a warning, so that one typo doesn't stop a whole act from loading.
+    fn build<S: AsRef<str>>(position: Vector2, kind: String, flags: &[S]) -> Result<Entity, String> {
+        let kind = canonical_kind(kind);
+        if entity_kind(&kind).is_none() {
+            eprintln!("Warning: Unknown entity kind {:?} at {} {}, using a placeholder instead", kind, position.x, position.y);
         }
+        let flags = parse_flags(&kind, flags)?;
+
+        Ok(Entity::with_kind(position, kind, flags))
+    }
+
+    /// Sets up an entity as described by its kind, if the kind exists
+    fn with_kind(position: Vector2, kind: String, flags: EntityFlags) -> Entity {
+        let definition = entity_kind(&kind);
 
         Entity {
             position,
+            spawn_position: position,
+            temporary: false,
+            dormant: false,
+            velocity: definition.map(|k| k.velocity).unwrap_or(Vector2 { x: 0.0, y: 0.0 }),
+            rotation: 0.0,
+            flipped: false,
+            animations: definition
+                .map(|k| k.animations.iter().cloned().map(Animation::new).collect())
+                .unwrap_or_default(),
+            current_animation: 0,
+            behavior: definition.and_then(|k| behavior_for(&k.behavior)),
+            spawned: false,
+            removed: false,
             kind,
             flags,
         }
     }
+
+    /// The definition of this entity's kind, if it exists
+    pub fn kind(&self) -> Option<&'static EntityKind> {
+        entity_kind(&self.kind)
+    }
+
+    /// Whether the entity's kind doesn't exist, so it just sits there as a marker
+    pub fn is_placeholder(&self) -> bool {
+        self.kind().is_none()
+    }
+
+    pub fn position(&self) -> Vector2 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vector2) {
+        self.position = position;
+    }
+
+    pub fn velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vector2) {
+        self.velocity = velocity;
+    }
+
+    pub fn flags(&self) -> &EntityFlags {
+        &self.flags
+    }
+
+    /// Starts playing the animation with the given image from the beginning,
+    /// unless it's already playing
+    pub fn set_animation(&mut self, image: &str) {
+        if let Some(idx) = self.kind().and_then(|k| k.animation(image)) {
+            self.set_animation_index(idx);
+        }
+    }
+
+    /// Like `set_animation`, but picking the animation by its place in the entity's definition
+    pub fn set_animation_index(&mut self, idx: usize) {
+        if idx != self.current_animation && idx < self.animations.len() {
+            self.current_animation = idx;
+            self.animations[idx].restart();
+        }
+    }
+
+    /// Sets how fast the current animation plays, where 0 holds it on its current frame
+    pub fn set_animation_speed(&mut self, speed: f64) {
+        if let Some(anim) = self.animations.get_mut(self.current_animation) {
+            anim.set_speed(speed);
+        }
+    }
+
+    pub fn set_animation_frame(&mut self, frame: usize) {
+        if let Some(anim) = self.animations.get_mut(self.current_animation) {
+            anim.set_frame(frame);
+        }
+    }
+
+    /// Whether the current animation has played through its last frame
+    pub fn animation_finished(&self) -> bool {
+        match self.animations.get(self.current_animation) {
+            Some(anim) => anim.is_finished(),
+            None => true,
+        }
+    }
+
+    /// How many ticks the current animation takes to play through once
+    pub fn animation_length(&self) -> u32 {
+        self.animations.get(self.current_animation).map(Animation::length).unwrap_or(0)
+    }
+
+    /// Takes the entity out of the game at the end of this tick
+    pub fn remove(&mut self) {
+        self.removed = true;
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    pub fn spawn_position(&self) -> Vector2 {
+        self.spawn_position
+    }
+
+    /// Marks the entity as something that only exists for as long as it stays in range, like a projectile
+    pub fn set_temporary(&mut self) {
+        self.temporary = true;
+    }
+
+    pub fn is_temporary(&self) -> bool {
+        self.temporary
+    }
+
+    /// Whether the entity has been destroyed and is just finishing off,
+    /// so that it shouldn't come back if it goes dormant
+    pub fn is_destroyed(&self) -> bool {
+        self.behavior.as_ref().filter(|behavior| behavior.is_destroyed()).is_some()
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.dormant
+    }
+
+    pub fn activate(&mut self) {
+        self.dormant = false;
+    }
+
+    /// Puts the entity to sleep, back the way it was first placed,
+    /// so that it starts over the next time it's activated
+    pub fn deactivate(&mut self) {
+        let kind = std::mem::take(&mut self.kind);
+        let flags = std::mem::take(&mut self.flags);
+        *self = Entity::with_kind(self.spawn_position, kind, flags);
+        self.dormant = true;
+    }
+
+    /// Where the entity can be touched, or nothing if its kind doesn't exist.
+    /// Turning the entity on its side turns the hitbox with it.
+    pub fn hitbox(&self) -> Option<Rect> {
+        let kind = self.kind()?;
+        let mut hitbox = Rect::new(
+            self.position.x as i32 + kind.hitbox_offset.0,
+            self.position.y as i32 + kind.hitbox_offset.1,
+            kind.hitbox_size.0,
+            kind.hitbox_size.1,
+        );
+
+        if (self.rotation % 180.0).abs() == 90.0 {
+            hitbox = Rect::from_center(hitbox.center(), hitbox.height(), hitbox.width());
+        }
+
+        Some(hitbox)
+    }
+
+    /// What the player stands on when the entity is solid, which is the top of its hitbox
+    /// unless its behavior says otherwise
+    pub fn top(&self) -> Option<SolidTop> {
+        match &self.behavior {
+            Some(behavior) => behavior.top(self),
+            None => self.hitbox_top(),
+        }
+    }
+
+    /// The top edge of the entity's hitbox, moving along with the entity
+    pub fn hitbox_top(&self) -> Option<SolidTop> {
+        let kind = self.kind()?;
+        let left = self.position.x + kind.hitbox_offset.0 as f64;
+        Some(SolidTop {
+            left,
+            right: left + kind.hitbox_size.0 as f64,
+            y: self.position.y + kind.hitbox_offset.1 as f64,
+            velocity: self.velocity,
+        })
+    }
+
+    /// Whether the player can stand on top of the entity
+    pub fn is_solid(&self, player: &Player) -> bool {
+        self.behavior.as_ref().filter(|behavior| behavior.is_solid(player)).is_some()
+    }
+
+    pub fn set_rotation(&mut self, degrees: f64) {
+        self.rotation = degrees;
+    }
+
+    pub fn set_flipped(&mut self, flipped: bool) {
+        self.flipped = flipped;
+    }
+
+    /// Runs the entity's behavior for one tick, then moves and animates it
+    pub fn update(&mut self, ctx: &mut EntityContext) {
+        if !self.spawned {
+            self.spawned = true;
+            self.with_behavior(|behavior, entity| behavior.on_spawn(entity, ctx));
+        }
+        self.with_behavior(|behavior, entity| behavior.update(entity, ctx));
+
+        self.position.x += self.velocity.x;
+        self.position.y += self.velocity.y;
+
+        if let Some(anim) = self.animations.get_mut(self.current_animation) {
+            anim.update();
+        }
+    }
+
+    pub fn touch_player(&mut self, ctx: &mut EntityContext) {
+        self.with_behavior(|behavior, entity| behavior.on_player_touch(entity, ctx));
+    }
+
+    fn with_behavior<F: FnOnce(&mut dyn EntityBehavior, &mut Entity)>(&mut self, f: F) {
+        if let Some(mut behavior) = self.behavior.take() {
+            f(behavior.as_mut(), self);
+            self.behavior = Some(behavior);
+        }
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f64(self.position.x);
+        hasher.write_f64(self.position.y);
+        hasher.write_f64(self.velocity.x);
+        hasher.write_f64(self.velocity.y);
+        hasher.write_f64(self.rotation);
+        self.flipped.hash(hasher);
+        self.kind.hash(hasher);
+        self.flags.hash_state(hasher);
+        hasher.write_f64(self.spawn_position.x);
+        hasher.write_f64(self.spawn_position.y);
+        self.temporary.hash(hasher);
+        self.dormant.hash(hasher);
+        hasher.write_usize(self.current_animation);
+        for anim in self.animations.iter() {
+            anim.hash_state(hasher);
+        }
+        if let Some(behavior) = &self.behavior {
+            behavior.hash_state(hasher);
+        }
+        self.spawned.hash(hasher);
+        self.removed.hash(hasher);
+    }
+}
+
+/// Entities are the same if they would be saved the same way, no matter how far along they are
+impl PartialEq for Entity {
+    fn eq(&self, other: &Entity) -> bool {
+        self.position == other.position && self.kind == other.kind && self.flags == other.flags
+    }
 }
 
 impl fmt::Display for Entity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} {} {} ", self.position.x, self.position.y, self.kind)?;
-        for flag in self.flags.iter() {
+        for flag in self.flags.given() {
             write!(f, "{} ", flag)?;
         }
         Ok(())
@@ -109,19 +491,43 @@ impl FromStr for Entity {
             .ok_or_else(|| "Expected name".to_string())?
             .to_string();
 
-        let flags = s.map(String::from).collect::<Vec<String>>();
-
-        Ok(Entity { position: Vector2 { x, y }, kind, flags })
+        Entity::build(Vector2 { x, y }, kind, &s.collect::<Vec<&str>>())
     }
 }
 
 impl Renderable for Entity {
     fn render<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera) -> Result<(), String> {
-        let data = ENTITY_DATA.read().unwrap();
-        let anims = data.get(&self.kind).unwrap();
+        if self.is_placeholder() {
+            let x = (self.position.x - camera.position.x) as i32;
+            let y = (self.position.y - camera.position.y) as i32;
+            let size = PLACEHOLDER_SIZE as i32;
+            canvas.set_draw_color(Color::from(PLACEHOLDER_COLOR));
+            canvas.draw_rect(Rect::new(x, y, PLACEHOLDER_SIZE, PLACEHOLDER_SIZE))?;
+            canvas.draw_line((x, y), (x + size - 1, y + size - 1))?;
+            return canvas.draw_line((x + size - 1, y), (x, y + size - 1));
+        }
+
+        if let Some(anim) = self.animations.get(self.current_animation) {
+            if self.rotation == 0.0 {
+                let offsets = match &self.behavior {
+                    Some(behavior) => behavior.sprite_offsets(self),
+                    None => vec![Vector2 { x: 0.0, y: 0.0 }],
+                };
+                for offset in offsets {
+                    let position = Vector2 { x: self.position.x + offset.x, y: self.position.y + offset.y };
+                    anim.render(canvas, camera, position, self.flipped)?;
+                }
+                return Ok(());
+            }
 
-        if let Some(anim) = anims.get(0) {
-            (self.position, anim).render(canvas, camera)
+            let pivot = match self.kind() {
+                Some(kind) => Vector2 {
+                    x: self.position.x + kind.hitbox_offset.0 as f64 + kind.hitbox_size.0 as f64 / 2.0,
+                    y: self.position.y + kind.hitbox_offset.1 as f64 + kind.hitbox_size.1 as f64 / 2.0,
+                },
+                None => self.position,
+            };
+            anim.render_rotated(canvas, camera, self.position, self.flipped, self.rotation, pivot)
         } else {
             Ok(())
         }
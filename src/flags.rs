@@ -0,0 +1,185 @@
+use crate::game::StateHasher;
+use std::fmt;
+use std::hash::Hash;
+
+/// What kind of value a flag holds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FlagType {
+    /// One of a fixed set of names
+    Choice(&'static [&'static str]),
+    /// A whole number from `min` to `max`, inclusive
+    Integer { min: i64, max: i64 },
+    Number,
+    /// Either `true` or `false`
+    Boolean,
+}
+
+/// One flag that a behavior class takes, in the order they're written after the entity's kind
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlagSpec {
+    pub name: &'static str,
+    pub kind: FlagType,
+    /// Used when the flag is left out, or nothing if it has to be given
+    pub default: Option<&'static str>,
+}
+
+impl FlagSpec {
+    fn parse(&self, s: &str) -> Result<FlagValue, String> {
+        match self.kind {
+            FlagType::Choice(choices) => match choices.iter().find(|choice| **choice == s) {
+                Some(choice) => Ok(FlagValue::Choice(choice)),
+                None => Err(format!("Invalid {} flag {:?}, expected one of {}", self.name, s, choices.join(", "))),
+            },
+            FlagType::Integer { min, max } => match s.parse() {
+                Ok(value) if value >= min && value <= max => Ok(FlagValue::Integer(value)),
+                Ok(_) if max == i64::MAX => Err(format!("Invalid {} flag {:?}, expected at least {}", self.name, s, min)),
+                Ok(_) => Err(format!("Invalid {} flag {:?}, expected {} to {}", self.name, s, min, max)),
+                Err(_) => Err(format!("Invalid {} flag {:?}, expected a whole number", self.name, s)),
+            },
+            FlagType::Number => s
+                .parse()
+                .map(FlagValue::Number)
+                .map_err(|_| format!("Invalid {} flag {:?}, expected a number", self.name, s)),
+            FlagType::Boolean => s
+                .parse()
+                .map(FlagValue::Boolean)
+                .map_err(|_| format!("Invalid {} flag {:?}, expected true or false", self.name, s)),
+        }
+    }
+}
+
+/// Just the names of a table of choices, for the schema of the flag they're picked with
+pub const fn choice_names<T, const N: usize>(choices: &[(&'static str, T); N]) -> [&'static str; N] {
+    let mut names = [""; N];
+    let mut idx = 0;
+    while idx < N {
+        names[idx] = choices[idx].0;
+        idx += 1;
+    }
+    names
+}
+
+/// Looks up what a name in a table of choices stands for
+fn find_choice<T: Copy>(choices: &[(&'static str, T)], name: &str) -> Option<T> {
+    choices.iter().find(|(choice, _)| *choice == name).map(|(_, value)| *value)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlagValue {
+    Choice(&'static str),
+    Integer(i64),
+    Number(f64),
+    Boolean(bool),
+    /// A flag on an entity whose kind doesn't exist, so there's nothing to check it against
+    Unchecked(String),
+}
+
+impl fmt::Display for FlagValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagValue::Choice(choice) => write!(f, "{}", choice),
+            FlagValue::Integer(value) => write!(f, "{}", value),
+            FlagValue::Number(value) => write!(f, "{}", value),
+            FlagValue::Boolean(value) => write!(f, "{}", value),
+            FlagValue::Unchecked(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// The flags of an entity, checked against its behavior class's schema
+/// and with any that were left out filled in from their defaults
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EntityFlags {
+    schema: &'static [FlagSpec],
+    values: Vec<FlagValue>,
+    /// How many of the flags were actually written, so that saving leaves the rest out again
+    given: usize,
+}
+
+impl EntityFlags {
+    pub fn parse<S: AsRef<str>>(schema: &'static [FlagSpec], flags: &[S]) -> Result<EntityFlags, String> {
+        if flags.len() > schema.len() {
+            let extra = flags[schema.len()].as_ref();
+            return Err(match schema.len() {
+                0 => format!("Unknown flag {:?}, this kind of entity doesn't take any", extra),
+                count => format!("Unknown flag {:?}, this kind of entity only takes {}", extra, count),
+            });
+        }
+
+        let values = schema
+            .iter()
+            .enumerate()
+            .map(|(idx, spec)| match (flags.get(idx), spec.default) {
+                (Some(flag), _) => spec.parse(flag.as_ref()),
+                (None, Some(default)) => spec.parse(default),
+                (None, None) => Err(format!("Missing {} flag", spec.name)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(EntityFlags { schema, values, given: flags.len() })
+    }
+
+    /// Keeps the flags as they are, for an entity whose kind doesn't exist
+    pub fn unchecked<S: AsRef<str>>(flags: &[S]) -> EntityFlags {
+        EntityFlags {
+            schema: &[],
+            values: flags.iter().map(|flag| FlagValue::Unchecked(flag.as_ref().to_string())).collect(),
+            given: flags.len(),
+        }
+    }
+
+    fn get(&self, name: &str) -> &FlagValue {
+        self.schema
+            .iter()
+            .position(|spec| spec.name == name)
+            .map(|idx| &self.values[idx])
+            .unwrap_or_else(|| panic!("No flag named {:?}", name))
+    }
+
+    /// The value of a choice flag. Panics if there's no choice flag with the given name.
+    pub fn choice(&self, name: &str) -> &'static str {
+        match self.get(name) {
+            FlagValue::Choice(choice) => choice,
+            other => panic!("Flag {:?} is not a choice: {:?}", name, other),
+        }
+    }
+
+    /// What a choice flag stands for, out of the table its schema was made from with `choice_names`
+    pub fn choice_of<T: Copy>(&self, name: &str, choices: &[(&'static str, T)]) -> T {
+        let choice = self.choice(name);
+        find_choice(choices, choice).unwrap_or_else(|| panic!("Flag {:?} has no meaning for {:?}", name, choice))
+    }
+
+    pub fn integer(&self, name: &str) -> i64 {
+        match self.get(name) {
+            FlagValue::Integer(value) => *value,
+            other => panic!("Flag {:?} is not an integer: {:?}", name, other),
+        }
+    }
+
+    pub fn number(&self, name: &str) -> f64 {
+        match self.get(name) {
+            FlagValue::Number(value) => *value,
+            other => panic!("Flag {:?} is not a number: {:?}", name, other),
+        }
+    }
+
+    pub fn boolean(&self, name: &str) -> bool {
+        match self.get(name) {
+            FlagValue::Boolean(value) => *value,
+            other => panic!("Flag {:?} is not a boolean: {:?}", name, other),
+        }
+    }
+
+    /// The flags that were written, in order
+    pub fn given(&self) -> &[FlagValue] {
+        &self.values[..self.given]
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.given.hash(hasher);
+        for value in self.values.iter() {
+            value.to_string().hash(hasher);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use gamefox::camera::Camera;
+use gamefox::physical::{Physical, Vector2};
+use gamefox::renderable::Renderable;
+use crate::behavior::{EntityContext, GameEvent};
+use crate::block::TerrainGetter;
+use crate::controls::{Buttons, Input};
+use crate::entity::Entity;
+use crate::goal::ActClear;
+use crate::lost_rings::{self, LostRing};
+use crate::player::{Player, SolidTop};
+use crate::rng::Rng;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+use std::hash::{Hash, Hasher};
+
+/// How much of the act fits on the screen, in pixels
+pub const SCREEN_SIZE: (u32, u32) = (800, 600);
+/// Where on the screen the camera keeps the player
+pub const FOCUS_ON_SCREEN: (f64, f64) = (200.0, 300.0);
+/// How far past the edges of the screen entities stay active
+pub const ACTIVE_MARGIN: i32 = 128;
+
+/// Everything in an act that changes while playing it.
+///
+/// Stepping two of these that started out the same with the same inputs
+/// always ends up with the same state, which `state_hash` can be used to check.
+pub struct Game {
+    pub player: Player,
+    pub entities: Vec<Entity>,
+    pub lost_rings: Vec<LostRing>,
+    pub rng: Rng,
+    /// Set once the goal post has been passed
+    act_clear: Option<ActClear>,
+    input: Input,
+    tick: u64,
+}
+
+impl Game {
+    pub fn new(player: Player, entities: Vec<Entity>, seed: u64) -> Game {
+        Game {
+            player,
+            entities,
+            lost_rings: Vec::new(),
+            rng: Rng::new(seed),
+            act_clear: None,
+            input: Input::default(),
+            tick: 0,
+        }
+    }
+
+    /// How many ticks have been simulated so far
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// How long the act has been going on for, in ticks, which stops counting once it's cleared
+    pub fn act_time(&self) -> u64 {
+        match &self.act_clear {
+            Some(clear) => clear.act_time,
+            None => self.tick,
+        }
+    }
+
+    pub fn act_clear(&self) -> Option<&ActClear> {
+        self.act_clear.as_ref()
+    }
+
+    /// What the camera should be centered on, which stays put once the act has been cleared
+    pub fn camera_focus(&self, alpha: f64) -> Vector2 {
+        match &self.act_clear {
+            Some(clear) => clear.focus,
+            None => self.player.interpolated_position(alpha),
+        }
+    }
+
+    /// Where the top left corner of the screen is in the act
+    pub fn camera_position(&self, alpha: f64) -> Vector2 {
+        let focus = self.camera_focus(alpha);
+        Vector2 { x: focus.x - FOCUS_ON_SCREEN.0, y: focus.y - FOCUS_ON_SCREEN.1 }
+    }
+
+    /// Wakes up entities that have come into range of the camera, and puts the ones that have left it to sleep.
+    /// Anything destroyed or temporary is gone for good instead.
+    fn update_activation(&mut self) {
+        let camera = self.camera_position(1.0);
+        let window = Rect::new(
+            camera.x as i32 - ACTIVE_MARGIN,
+            camera.y as i32 - ACTIVE_MARGIN,
+            SCREEN_SIZE.0 + 2 * ACTIVE_MARGIN as u32,
+            SCREEN_SIZE.1 + 2 * ACTIVE_MARGIN as u32,
+        );
+        let in_range = |position: Vector2| window.contains_point((position.x as i32, position.y as i32));
+
+        for entity in self.entities.iter_mut() {
+            let spawn_in_range = in_range(entity.spawn_position());
+            if !entity.is_active() {
+                if spawn_in_range {
+                    entity.activate();
+                }
+            } else if !in_range(entity.position()) {
+                if entity.is_temporary() || entity.is_destroyed() {
+                    entity.remove();
+                } else if !spawn_in_range {
+                    // Anything that wanders off stays awake until where it started is out of range too
+                    entity.deactivate();
+                }
+            }
+        }
+        self.entities.retain(|e| !e.is_removed());
+    }
+
+    /// Simulates one tick, with the given buttons held down.
+    /// Once the act has been cleared, the player isn't in control anymore.
+    pub fn step(&mut self, getter: &TerrainGetter, buttons: Buttons) {
+        let buttons = if self.act_clear.is_some() { Buttons::NONE } else { buttons };
+        self.input = self.input.next(buttons);
+        let mut events = Vec::new();
+
+        self.update_activation();
+
+        let mut ctx = EntityContext { terrain: getter, player: &mut self.player, events: &mut events };
+        for entity in self.entities.iter_mut().filter(|e| e.is_active()) {
+            entity.update(&mut ctx);
+        }
+
+        let player = &self.player;
+        let solids: Vec<SolidTop> = self.entities
+            .iter()
+            .filter(|e| e.is_active() && e.is_solid(player) && !e.is_removed())
+            .filter_map(|e| e.top())
+            .collect();
+        self.player.update(getter, self.input, &solids);
+
+        let player_hitbox = self.player.hitbox();
+        let mut ctx = EntityContext { terrain: getter, player: &mut self.player, events: &mut events };
+        for entity in self.entities.iter_mut().filter(|e| e.is_active() && !e.is_removed()) {
+            if entity.hitbox().filter(|hitbox| hitbox.has_intersection(player_hitbox)).is_some() {
+                entity.touch_player(&mut ctx);
+            }
+        }
+        self.entities.retain(|e| !e.is_removed());
+
+        lost_rings::update_lost_rings(&mut self.lost_rings, &mut self.player, getter);
+
+        for event in events {
+            self.handle_event(event);
+        }
+
+        if let Some(clear) = self.act_clear.as_mut() {
+            clear.update(&mut self.player);
+        }
+
+        self.tick += 1;
+    }
+
+    fn handle_event(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::Spawn(mut entity) => {
+                entity.set_temporary();
+                self.entities.push(entity);
+            }
+            GameEvent::HurtPlayer { source_x } => self.hurt_player(source_x),
+            GameEvent::ReachGoal { x } => {
+                if self.act_clear.is_none() {
+                    let focus = Vector2 { x, y: self.player.get_position().y };
+                    self.act_clear = Some(ActClear::new(focus, self.tick, self.player.rings()));
+                }
+            }
+        }
+    }
+
+    /// Hits the player from something at the given x position, scattering any rings they had
+    pub fn hurt_player(&mut self, source_x: f64) {
+        let lost = self.player.hurt(source_x);
+        LostRing::scatter(self.player.get_position(), lost, self.player.physics(), &mut self.rng, &mut self.lost_rings);
+    }
+
+    /// A hash of the whole simulation state, which only matches another game's
+    /// if both have been playing out exactly the same way
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_u64(self.tick);
+        self.input.hash(&mut hasher);
+        self.player.hash_state(&mut hasher);
+        for entity in self.entities.iter() {
+            entity.hash_state(&mut hasher);
+        }
+        for ring in self.lost_rings.iter() {
+            ring.hash_state(&mut hasher);
+        }
+        if let Some(clear) = &self.act_clear {
+            clear.hash_state(&mut hasher);
+        }
+        hasher.write_u64(self.rng.clone().next_u64());
+        hasher.finish()
+    }
+
+    pub fn render_interpolated<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera, alpha: f64) -> Result<(), String> {
+        for entity in self.entities.iter().filter(|e| e.is_active()) {
+            entity.render(canvas, camera)?;
+        }
+        for ring in self.lost_rings.iter() {
+            ring.render(canvas, camera)?;
+        }
+        self.player.render_interpolated(canvas, camera, alpha)
+    }
+}
+
+/// FNV-1a, which unlike the standard library's hasher is guaranteed
+/// to give the same results on every platform and Rust version
+pub struct StateHasher(u64);
+
+impl StateHasher {
+    pub fn new() -> StateHasher {
+        StateHasher(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub fn write_f64(&mut self, value: f64) {
+        self.write_u64(value.to_bits());
+    }
+}
+
+impl Default for StateHasher {
+    fn default() -> StateHasher {
+        StateHasher::new()
+    }
+}
+
+impl Hasher for StateHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100_0000_01b3);
+        }
+    }
+
+    // The default implementations use native endianness, so always use little-endian instead
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_u64(value as u64);
+    }
+}
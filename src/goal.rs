@@ -0,0 +1,153 @@
+use gamefox::physical::{Physical, Vector2};
+use crate::behavior::{EntityBehavior, EntityContext, GameEvent};
+use crate::entity::Entity;
+use crate::game::StateHasher;
+use crate::player::Player;
+use crate::timing::TICKS_PER_SECOND;
+use std::hash::{Hash, Hasher};
+
+/// How long the sign spins after being passed, before the results come up
+pub const SIGN_SPIN_TIME: u32 = 120;
+/// The frame of the sign with Robotnik on it, shown until it gets passed
+const REST_FRAME: usize = 0;
+/// The frame of the sign with Tails on it, shown once it stops spinning
+const CLEARED_FRAME: usize = 4;
+
+/// How long the results stay up before the bonuses start counting into the score
+const TALLY_DELAY: u32 = 180;
+/// How many points of bonus get counted into the score every tick
+const TALLY_RATE: u32 = 100;
+/// How long the final score stays up before moving on to the next act
+const FINISH_DELAY: u32 = 180;
+
+const RING_BONUS_PER_RING: u32 = 100;
+/// The time bonus for getting through the act in under each number of seconds
+const TIME_BONUSES: &[(u64, u32)] = &[
+    (30, 50000),
+    (45, 10000),
+    (60, 5000),
+    (90, 4000),
+    (120, 3000),
+    (180, 1000),
+    (600, 100),
+];
+
+/// The sign at the end of an act, which spins around when the player runs past it
+#[derive(Debug, Default)]
+pub struct GoalPost {
+    /// Ticks left spinning, once passed
+    spin_left: Option<u32>,
+}
+
+impl EntityBehavior for GoalPost {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        entity.set_animation_frame(REST_FRAME);
+        entity.set_animation_speed(0.0);
+    }
+
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        match self.spin_left {
+            None => {
+                let center = entity.hitbox().map(|h| h.center().x() as f64).unwrap_or(entity.position().x);
+                if ctx.player.get_position().x >= center {
+                    self.spin_left = Some(SIGN_SPIN_TIME);
+                    entity.set_animation_speed(1.0);
+                    ctx.events.push(GameEvent::ReachGoal { x: center });
+                }
+            }
+            Some(0) => {}
+            Some(1) => {
+                self.spin_left = Some(0);
+                entity.set_animation_frame(CLEARED_FRAME);
+                entity.set_animation_speed(0.0);
+            }
+            Some(ticks) => self.spin_left = Some(ticks - 1),
+        }
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.spin_left.hash(hasher);
+    }
+}
+
+/// The sequence after passing the goal post: the sign spins, the player strikes a pose,
+/// and then the results come up and the bonuses get counted into the score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActClear {
+    /// Where the camera stays locked for the rest of the act
+    pub focus: Vector2,
+    /// How long it took to get through the act, in ticks
+    pub act_time: u64,
+    /// Bonus points left to count into the score
+    pub time_bonus: u32,
+    pub ring_bonus: u32,
+    /// Ticks since passing the goal post
+    ticks: u32,
+    /// Ticks left before the act is over, once everything has been counted
+    finish_left: Option<u32>,
+}
+
+impl ActClear {
+    pub fn new(focus: Vector2, act_time: u64, rings: u32) -> ActClear {
+        let seconds = act_time / TICKS_PER_SECOND as u64;
+        let time_bonus = TIME_BONUSES
+            .iter()
+            .find(|(limit, _)| seconds < *limit)
+            .map(|(_, bonus)| *bonus)
+            .unwrap_or(0);
+
+        ActClear {
+            focus,
+            act_time,
+            time_bonus,
+            ring_bonus: rings * RING_BONUS_PER_RING,
+            ticks: 0,
+            finish_left: None,
+        }
+    }
+
+    /// Whether the sign has stopped, so the results should be showing
+    pub fn showing_results(&self) -> bool {
+        self.ticks >= SIGN_SPIN_TIME
+    }
+
+    /// Whether everything has been counted up and the next act should start
+    pub fn is_finished(&self) -> bool {
+        self.finish_left == Some(0)
+    }
+
+    pub fn update(&mut self, player: &mut Player) {
+        self.ticks += 1;
+        if self.ticks == SIGN_SPIN_TIME {
+            player.celebrate();
+        }
+
+        if self.ticks < SIGN_SPIN_TIME + TALLY_DELAY {
+            return;
+        }
+
+        match self.finish_left {
+            Some(ticks) => self.finish_left = Some(ticks.saturating_sub(1)),
+            None => {
+                let bonus = if self.time_bonus > 0 { &mut self.time_bonus } else { &mut self.ring_bonus };
+                let counted = (*bonus).min(TALLY_RATE);
+                *bonus -= counted;
+                player.add_score(counted);
+
+                if self.time_bonus == 0 && self.ring_bonus == 0 {
+                    self.finish_left = Some(FINISH_DELAY);
+                }
+            }
+        }
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f64(self.focus.x);
+        hasher.write_f64(self.focus.y);
+        hasher.write_u64(self.act_time);
+        hasher.write_u32(self.time_bonus);
+        hasher.write_u32(self.ring_bonus);
+        hasher.write_u32(self.ticks);
+        self.finish_left.hash(hasher);
+    }
+}
@@ -0,0 +1,90 @@
+use gamefox::asset_mgr::GraphicsHolder;
+use crate::goal::ActClear;
+use crate::player::Player;
+use crate::timing::TICKS_PER_SECOND;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+
+/// Every character in `FontGUI.png`, in order
+const FONT_CHARACTERS: &str = "0123456789:ABCDEFGHIJKLMNOPQRSTUVWXYZ.-,[]";
+const GLYPH_WIDTH: u32 = 10;
+const GLYPH_HEIGHT: u32 = 11;
+
+const MARGIN: i32 = 16;
+const LINE_HEIGHT: i32 = 16;
+/// How far to the right of each label its value gets drawn
+const VALUE_OFFSET: i32 = 70;
+
+/// Draws text in the HUD font with its top left corner at the given point on the screen.
+/// Lowercase letters are drawn as uppercase, and anything else not in the font is left as a gap.
+pub fn draw_text<T: RenderTarget>(canvas: &mut Canvas<T>, text: &str, x: i32, y: i32) -> Result<(), String> {
+    let holder = GraphicsHolder::get();
+    let (_, font) = holder.get_pair("FontGUI").ok_or_else(|| "Missing image FontGUI".to_string())?;
+
+    for (idx, c) in text.chars().enumerate() {
+        if let Some(glyph) = FONT_CHARACTERS.find(c.to_ascii_uppercase()) {
+            let src = Rect::new((glyph as u32 * GLYPH_WIDTH) as i32, 0, GLYPH_WIDTH, GLYPH_HEIGHT);
+            let dst = Rect::new(x + (idx as u32 * GLYPH_WIDTH) as i32, y, GLYPH_WIDTH, GLYPH_HEIGHT);
+            canvas.copy(font, src, dst)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats a number of ticks as minutes and seconds, e.g. `1:05`
+pub fn format_time(ticks: u64) -> String {
+    let seconds = ticks / TICKS_PER_SECOND as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Draws the score, time and ring counters in the top left corner and the lives counter in the bottom left
+pub fn render_hud<T: RenderTarget>(canvas: &mut Canvas<T>, player: &Player, act_time: u64) -> Result<(), String> {
+    draw_text(canvas, "SCORE", MARGIN, MARGIN)?;
+    draw_text(canvas, &player.score().to_string(), MARGIN + VALUE_OFFSET, MARGIN)?;
+
+    let time_y = MARGIN + LINE_HEIGHT;
+    draw_text(canvas, "TIME", MARGIN, time_y)?;
+    draw_text(canvas, &format_time(act_time), MARGIN + VALUE_OFFSET, time_y)?;
+
+    let rings_y = time_y + LINE_HEIGHT;
+    draw_text(canvas, "RINGS", MARGIN, rings_y)?;
+    draw_text(canvas, &player.rings().to_string(), MARGIN + VALUE_OFFSET, rings_y)?;
+
+    let (_, height) = canvas.output_size()?;
+    let lives_y = height as i32 - MARGIN - 16;
+    {
+        let holder = GraphicsHolder::get();
+        let (_, icon) = holder.get_pair("Lives").ok_or_else(|| "Missing image Lives".to_string())?;
+        canvas.copy(icon, None, Rect::new(MARGIN, lives_y, 48, 16))?;
+    }
+    draw_text(canvas, &player.lives().to_string(), MARGIN + 32, lives_y + 5)
+}
+
+/// Draws `GAME OVER` in the middle of the screen
+pub fn render_game_over<T: RenderTarget>(canvas: &mut Canvas<T>) -> Result<(), String> {
+    let (width, height) = canvas.output_size()?;
+    let text = "GAME OVER";
+    let x = (width as i32 - (text.len() as u32 * GLYPH_WIDTH) as i32) / 2;
+    draw_text(canvas, text, x, height as i32 / 2)
+}
+
+/// Draws the results of clearing an act in the middle of the screen, with the bonuses still left to count
+pub fn render_results<T: RenderTarget>(canvas: &mut Canvas<T>, clear: &ActClear, act_number: u32, player: &Player) -> Result<(), String> {
+    let (width, height) = canvas.output_size()?;
+    let title = format!("TAILS GOT THROUGH ACT {}", act_number);
+    let title_x = (width as i32 - (title.len() as u32 * GLYPH_WIDTH) as i32) / 2;
+    let mut y = height as i32 / 3;
+    draw_text(canvas, &title, title_x, y)?;
+
+    let label_x = width as i32 / 2 - 120;
+    let value_x = width as i32 / 2 + 60;
+    y += LINE_HEIGHT * 2;
+    for (label, value) in [("SCORE", player.score()), ("TIME BONUS", clear.time_bonus), ("RING BONUS", clear.ring_bonus)].iter() {
+        draw_text(canvas, label, label_x, y)?;
+        draw_text(canvas, &value.to_string(), value_x, y)?;
+        y += LINE_HEIGHT;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+//! The engine behind Project Tails.
+//!
+//! A `Zone` holds the blocks and collision tiles that an `ActFile` is laid out with,
+//! `Zone::terrain` gives the `TerrainGetter` used for collision,
+//! and a `Game` is the simulation that gets stepped one tick at a time and rendered.
+//! None of this needs a window except for actually drawing things,
+//! so everything can run headless, e.g. with a `Scenario`.
+
+pub mod act;
+pub mod animation;
+pub mod badnik;
+pub mod behavior;
+pub mod block;
+pub mod bridge;
+pub mod controls;
+pub mod entity;
+pub mod flags;
+pub mod game;
+pub mod goal;
+pub mod hud;
+pub mod lost_rings;
+pub mod monitor;
+pub mod path_switch;
+pub mod physics;
+pub mod platform;
+pub mod player;
+pub mod replay;
+pub mod ring;
+pub mod rng;
+pub mod scenario;
+pub mod spikes;
+pub mod sound;
+pub mod spring;
+pub mod timing;
+pub mod zone;
+
+pub use act::ActFile;
+pub use block::TerrainGetter;
+pub use controls::Buttons;
+pub use game::Game;
+pub use physics::PhysicsProfile;
+pub use player::Player;
+pub use scenario::Scenario;
+pub use zone::Zone;
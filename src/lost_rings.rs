@@ -0,0 +1,133 @@
+use gamefox::camera::Camera;
+use gamefox::physical::Vector2;
+use crate::animation::{Animation, AnimationData};
+use crate::block::TerrainGetter;
+use crate::game::StateHasher;
+use crate::physics::PhysicsProfile;
+use crate::player::{find_ground_height, Player};
+use crate::rng::Rng;
+use lazy_static::lazy_static;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::time::Duration;
+
+lazy_static! {
+    static ref RING_ANIMATION: Arc<AnimationData> = {
+        let mut data = AnimationData::load("Ring").unwrap();
+        data.set_frames(4, Duration::from_millis(133));
+        Arc::new(data)
+    };
+}
+
+/// The most rings that get scattered at once, no matter how many the player had
+const MAX_LOST_RINGS: u32 = 32;
+/// How far each ring's speed can randomly stray from the rest, as a fraction
+const SCATTER_SPEED_VARIATION: f64 = 0.1;
+/// How much of its speed a ring keeps when bouncing off the ground
+const BOUNCE_FACTOR: f64 = 0.75;
+
+/// How long a ring sticks around before disappearing
+const LIFETIME: u32 = 256;
+/// How long it takes before a ring can be picked up again
+const COLLECT_DELAY: u32 = 64;
+const RING_SIZE: u32 = 16;
+
+/// A ring bouncing around after the player got hit
+pub struct LostRing {
+    position: Vector2,
+    velocity: Vector2,
+    age: u32,
+    animation: Animation,
+}
+
+impl LostRing {
+    /// Throws up to 32 of the given number of rings out in a fan around the given position,
+    /// alternating left and right, with any after the first 16 in a slower second circle.
+    pub fn scatter(position: Vector2, count: u32, physics: &PhysicsProfile, rng: &mut Rng, rings: &mut Vec<LostRing>) {
+        let start_angle = 101.25f64.to_radians();
+        let mut angle = start_angle;
+        let mut speed = physics.ring_scatter_speed;
+
+        for i in 0..count.min(MAX_LOST_RINGS) {
+            if i == 16 {
+                angle = start_angle;
+                speed /= 2.0;
+            }
+
+            let ring_speed = speed * rng.range(1.0 - SCATTER_SPEED_VARIATION, 1.0 + SCATTER_SPEED_VARIATION);
+            let mut velocity = Vector2 { x: angle.cos() * ring_speed, y: -angle.sin() * ring_speed };
+            if i % 2 == 1 {
+                velocity.x = -velocity.x;
+                angle += 22.5f64.to_radians();
+            }
+
+            rings.push(LostRing {
+                position: Vector2 { x: position.x, y: position.y - RING_SIZE as f64 },
+                velocity,
+                age: 0,
+                animation: Animation::new(RING_ANIMATION.clone()),
+            });
+        }
+    }
+
+    fn update(&mut self, gravity: f64, getter: &TerrainGetter) {
+        self.age += 1;
+
+        self.position.x += self.velocity.x;
+        self.position.y += self.velocity.y;
+        self.velocity.y += gravity;
+
+        let mut bottom_middle = self.position;
+        bottom_middle.x += RING_SIZE as f64 / 2.0;
+        bottom_middle.y += RING_SIZE as f64;
+        if let Some((ground, _angle)) = find_ground_height(bottom_middle, 0.0, 0.0, 0, getter) {
+            let ground = ground as f64;
+            if bottom_middle.y >= ground && self.velocity.y > 0.0 {
+                self.position.y = ground - RING_SIZE as f64;
+                self.velocity.y *= -BOUNCE_FACTOR;
+            }
+        }
+
+        self.animation.update();
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f64(self.position.x);
+        hasher.write_f64(self.position.y);
+        hasher.write_f64(self.velocity.x);
+        hasher.write_f64(self.velocity.y);
+        hasher.write_u32(self.age);
+        self.animation.hash_state(hasher);
+    }
+
+    fn hitbox(&self) -> Rect {
+        Rect::new(self.position.x as i32, self.position.y as i32, RING_SIZE, RING_SIZE)
+    }
+
+    pub fn render<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera) -> Result<(), String> {
+        self.animation.render(canvas, camera, self.position, false)
+    }
+}
+
+/// Moves every lost ring, letting the player pick them back up
+/// and dropping the ones that have been around too long.
+pub fn update_lost_rings(rings: &mut Vec<LostRing>, player: &mut Player, getter: &TerrainGetter) {
+    let player_hitbox = player.hitbox();
+    let gravity = player.physics().ring_gravity;
+
+    rings.retain(|ring| ring.age < LIFETIME);
+    for ring in rings.iter_mut() {
+        ring.update(gravity, getter);
+    }
+
+    rings.retain(|ring| {
+        if ring.age >= COLLECT_DELAY && ring.hitbox().has_intersection(player_hitbox) {
+            player.collect_ring();
+            false
+        } else {
+            true
+        }
+    });
+}
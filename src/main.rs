@@ -1,23 +1,17 @@
-mod block;
-mod player;
-mod entity;
-mod act;
-
-use gamefox::renderable::Renderable;
 use gamefox::camera::Camera;
-use gamefox::physical::{Physical, Vector2};
+use gamefox::physical::Vector2;
 use gamefox::input;
 use gamefox::asset_mgr::GraphicsHolder;
 
-use act::ActFile;
-use player::Player;
-use entity::Entity;
-use block::{Block, Tileset};
+use project_tails::{controls, hud, sound};
+use project_tails::game::SCREEN_SIZE;
+use project_tails::replay::Replay;
+use project_tails::timing::{FixedTimestep, FrameStats, TICKS_PER_SECOND};
+use project_tails::{ActFile, Game, PhysicsProfile, Player, Zone};
 
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use sdl2::rect::Point;
 use sdl2::{Sdl, VideoSubsystem};
 use sdl2::video::WindowContext;
 use sdl2::render::{TextureCreator, WindowCanvas};
@@ -38,12 +32,13 @@ impl SdlSystem {
         let sdl_context = sdl2::init().unwrap();
         let video_subsystem = sdl_context.video().unwrap();
         let window = video_subsystem
-            .window("Project Tails v2", 800, 600)
+            .window("Project Tails v2", SCREEN_SIZE.0, SCREEN_SIZE.1)
             .position_centered()
             .build()
             .unwrap();
     
-        let canvas = window.into_canvas().build().unwrap();
+        // Presenting waits for vsync, so frames drawn between ticks with --interpolate don't spin as fast as possible
+        let canvas = window.into_canvas().present_vsync().build().unwrap();
         let creator = Box::leak(Box::new(canvas.texture_creator()));
     
         SdlSystem {
@@ -55,129 +50,233 @@ impl SdlSystem {
     }
 }
 
+/// How long the game over screen shows before quitting
+const GAME_OVER_TIME: Duration = Duration::from_secs(5);
+
+fn exit_with_error(error: String) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(1);
+}
+
 fn main() {
     let matches = App::new("Project Tails v2")
         .version("0.0")
         .author("Salix")
         .about("A simple game")
-        .arg(Arg::with_name("debug")
-            .short("d")
-            .long("debug")
-            .help("Run in debug mode"))
+        .arg(Arg::with_name("act")
+            .long("act")
+            .takes_value(true)
+            .value_name("FILE")
+            .default_value("./assets/Act1Data.txt")
+            .help("Act file to play"))
+        .arg(Arg::with_name("physics")
+            .short("p")
+            .long("physics")
+            .takes_value(true)
+            .value_name("PROFILE")
+            .default_value("floaty")
+            .help("Physics profile from assets/PhysicsProfiles.json to use, e.g. classic or floaty"))
+        .arg(Arg::with_name("interpolate")
+            .long("interpolate")
+            .help("Smooth out rendering by blending between simulation ticks"))
+        .arg(Arg::with_name("timing")
+            .long("timing")
+            .help("Print how much of each frame's time budget is being used"))
+        .arg(Arg::with_name("seed")
+            .long("seed")
+            .takes_value(true)
+            .value_name("SEED")
+            .help("Seed for all gameplay randomness, so that the same inputs always play out the same way"))
+        .arg(Arg::with_name("hashes")
+            .long("hashes")
+            .help("Print a hash of the simulation state after every tick"))
+        .arg(Arg::with_name("record")
+            .long("record")
+            .takes_value(true)
+            .value_name("FILE")
+            .conflicts_with("replay")
+            .help("Record the inputs of this run to a replay file when quitting"))
+        .arg(Arg::with_name("replay")
+            .long("replay")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Play back a recorded run, using the act, physics and seed it was recorded with"))
         .get_matches();
 
-    if matches.is_present("debug") {
-        todo!("Debug mode");
-    }    
+    let replay = matches
+        .value_of("replay")
+        .map(|path| Replay::load(path).unwrap_or_else(|e| exit_with_error(e)));
+
+    let (act_path, physics_name, physics, seed) = match &replay {
+        Some(replay) => (replay.act.clone(), replay.physics_name.clone(), replay.physics.clone(), replay.seed),
+        None => {
+            let physics_name = matches.value_of("physics").unwrap().to_string();
+            let physics = PhysicsProfile::load(&physics_name).unwrap_or_else(|e| exit_with_error(e));
+
+            let seed = match matches.value_of("seed") {
+                Some(seed) => seed
+                    .parse::<u64>()
+                    .unwrap_or_else(|e| exit_with_error(format!("Invalid seed {:?}: {}", seed, e))),
+                None => SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64,
+            };
+
+            (matches.value_of("act").unwrap().to_string(), physics_name, physics, seed)
+        }
+    };
+    println!("Using seed {}", seed);
+
+    let mut recording = matches
+        .value_of("record")
+        .map(|_| Replay::new(act_path.clone(), physics_name, physics.clone(), seed));
     
     let mut sdl_system = SdlSystem::new();
 
     GraphicsHolder::load(Path::new("./assets/"), sdl_system.creator).ok().unwrap();
 
-    let blocks = block::load_blocks(Path::new("./assets/EmeraldHillZone/"), "Block").unwrap();
-    println!("Loaded {} blocks", blocks.len());
+    let audio = sdl_system.sdl_context.audio().unwrap_or_else(|e| exit_with_error(e));
 
-    let tileset = Tileset { tiles_per_row: 20, image: "EmeraldHillZone".to_string() };
+    let mut camera = Camera { position: Vector2 { x: 0.0, y: 0.0 }};
 
-    let mut act_file = std::fs::read_to_string("./assets/Act1Data.txt")
-        .unwrap()
-        .parse::<ActFile>()
-        .unwrap();
+    let interpolate = matches.is_present("interpolate");
+    let mut event_pump = sdl_system.sdl_context.event_pump().unwrap();
 
-    let act_file2 = format!("{}", act_file).parse::<ActFile>().unwrap();
-    assert_eq!(act_file, act_file2);
+    // The score and lives carry over from one act to the next
+    let mut player = Player::new(physics);
+    let mut act_path = act_path;
+    let mut zone: Option<Zone> = None;
+    'acts: loop {
+        let mut act_file = ActFile::load(&act_path).unwrap_or_else(|e| exit_with_error(e));
 
-    act_file.entities = vec![
-        Entity::new(Vector2 { x: 100.0, y: 300.0, }, "BEEBADNIK".to_string(), Vec::new()),
-        Entity::new(Vector2 { x: 100.0, y: 400.0, }, "BEEBADNIK".to_string(), Vec::new()),
-    ];
-    
-    println!("Loaded act with {} entities and a width of {}", act_file.entities.len(), act_file.width);
+        let act_file2 = format!("{}", act_file).parse::<ActFile>().unwrap();
+        assert_eq!(act_file, act_file2);
 
-    let mut camera = Camera { position: Vector2 { x: 0.0, y: 0.0 }};
+        println!("Loaded act with {} entities and a width of {}", act_file.entities.len(), act_file.width);
 
-    Block::add_graphics_multi(
-        blocks.iter().enumerate().map(|(i, block)| (format!("BLOCK{}", i), block)),
-        &tileset,
-        sdl_system.creator,
-    );
+        // Acts in the same zone share its blocks, so they only get loaded again when the zone changes
+        if zone.as_ref().filter(|zone| zone.name == act_file.zone).is_none() {
+            let loaded = Zone::load(&act_file.zone).unwrap_or_else(|e| exit_with_error(e));
+            println!("Loaded {} blocks", loaded.blocks.len());
+            loaded.add_graphics(sdl_system.creator);
+            zone = Some(loaded);
+        }
+        let zone = zone.as_ref().unwrap();
 
-    let collision_map = block::parse_collision_map("CollisionTiles");
+        let entities = std::mem::take(&mut act_file.entities);
+        let getter = zone.terrain(&act_file);
+        let mut game = Game::new(player, entities, seed);
 
-    let getter = block::TerrainGetter::new(
-        act_file.width,
-        &act_file.tiles,
-        &blocks,
-        &collision_map,
-    );
+        let mut timestep = FixedTimestep::new(TICKS_PER_SECOND);
+        let mut stats = FrameStats::new(timestep.tick_length());
+        let mut jingle = None;
+        let mut game_over_at = None;
 
-    let mut player = Player::new();
+        loop {
+            let ticks = timestep.advance();
+            let update_start = Instant::now();
 
-    let mut event_pump = sdl_system.sdl_context.event_pump().unwrap();
-    'running: loop {
-        for entity in act_file.entities.iter_mut() {
-            entity.update();
-        }
-        player.update(&getter);
-        camera.position = player.get_position();
-        camera.position.x -= 200.0;
-        camera.position.y -= 300.0;
-
-        let events = event_pump.poll_iter().collect::<Vec<_>>();
-        input::update(&events);
-        for event in events.clone() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => {
-                    break 'running;
+            for _ in 0..ticks {
+                // Input is read once per tick, so a key press is only ever seen by one update
+                let events = event_pump.poll_iter().collect::<Vec<_>>();
+                input::update(&events);
+                for event in events.clone() {
+                    match event {
+                        Event::Quit { .. }
+                        | Event::KeyDown {
+                            keycode: Some(Keycode::Escape),
+                            ..
+                        } => {
+                            break 'acts;
+                        }
+                        _ => {}
+                    }
+                }
+
+                let buttons = match &replay {
+                    Some(replay) => replay.input(game.tick()),
+                    None => controls::read_keyboard(),
+                };
+                if let Some(recording) = recording.as_mut() {
+                    recording.inputs.push(buttons);
+                }
+
+                game.step(&getter, buttons);
+                if matches.is_present("hashes") {
+                    println!("{} {:016x}", game.tick(), game.state_hash());
                 }
-                _ => {}
             }
-        }
 
-        sdl_system.canvas.set_draw_color(Color::RGB(20, 20, 20));
-        sdl_system.canvas.clear();
-        for r in 0..act_file.tiles.len() / act_file.width {
-            for c in 0..act_file.width {
-                let x = c * 128;
-                let y = r * 128;
-
-                let x = x as f64 - camera.position.x;
-                let y = y as f64 - camera.position.y;
-
-                sdl_system.canvas.set_draw_color(Color::RGB(255, 255, 255));
-                sdl_system.canvas.draw_line(
-                    Point::new(x as i32, y as i32),
-                    Point::new(x as i32, y as i32 + 600),
-                ).unwrap();
-                sdl_system.canvas.draw_line(
-                    Point::new(x as i32, y as i32),
-                    Point::new(x as i32 + 600, y as i32),
-                ).unwrap();
-
-                if let Some((block_idx, block_flags)) = act_file.tiles[r * act_file.width + c] {
-                    sdl_system.canvas.copy_ex(
-                        &GraphicsHolder::get().get_pair(&format!("BLOCK{}", block_idx)).unwrap().1,
-                        None,
-                        sdl2::rect::Rect::new(x as i32, y as i32, 128, 128),
-                        0.0,
-                        None,
-                        // TODO: Support more flags
-                        block_flags != 0,
-                        false,
-                    ).unwrap();
+            if let Some(clear) = game.act_clear() {
+                if clear.showing_results() && jingle.is_none() {
+                    jingle = Some(sound::play_wav(&audio, "StageClear").map_err(|e| eprintln!("Warning: {}", e)));
+                }
+
+                if clear.is_finished() {
+                    // Replays are recorded against a single act
+                    if replay.is_some() || recording.is_some() {
+                        break 'acts;
+                    }
+
+                    match act_file.next_act_path(&act_path) {
+                        Some(next) => act_path = next,
+                        None => {
+                            eprintln!("Warning: No act after {}", act_file.name);
+                            break 'acts;
+                        }
+                    }
+
+                    player = game.player;
+                    player.start_act();
+                    continue 'acts;
+                }
+            }
+
+            if game.player.is_game_over() {
+                let since = *game_over_at.get_or_insert_with(Instant::now);
+                if since.elapsed() >= GAME_OVER_TIME {
+                    break 'acts;
+                }
+            }
+
+            let update_time = update_start.elapsed();
+
+            // Without interpolation, nothing has changed on screen until the next tick
+            if ticks == 0 && !interpolate {
+                std::thread::sleep(timestep.time_until_next_tick());
+                continue;
+            }
+
+            let render_start = Instant::now();
+            let alpha = if interpolate { timestep.alpha() } else { 1.0 };
+
+            camera.position = game.camera_position(alpha);
+
+            sdl_system.canvas.set_draw_color(Color::RGB(20, 20, 20));
+            sdl_system.canvas.clear();
+            zone.render_act(&mut sdl_system.canvas, &camera, &act_file).unwrap();
+            game.render_interpolated(&mut sdl_system.canvas, &camera, alpha).unwrap();
+            hud::render_hud(&mut sdl_system.canvas, &game.player, game.act_time()).unwrap();
+            if let Some(clear) = game.act_clear().filter(|clear| clear.showing_results()) {
+                let number = act_file.number().unwrap_or(1);
+                hud::render_results(&mut sdl_system.canvas, clear, number, &game.player).unwrap();
+            }
+            if game.player.is_game_over() {
+                hud::render_game_over(&mut sdl_system.canvas).unwrap();
+            }
+            sdl_system.canvas.present();
+
+            stats.record_frame(ticks, update_time, render_start.elapsed());
+            if matches.is_present("timing") {
+                if let Some(report) = stats.report() {
+                    println!("{}", report);
                 }
             }
         }
-        for entity in act_file.entities.iter() {
-            entity.render(&mut sdl_system.canvas, &camera).unwrap();
-        }
-        player.render(&mut sdl_system.canvas, &camera).unwrap();
-        sdl_system.canvas.present();
+    }
 
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    if let (Some(path), Some(recording)) = (matches.value_of("record"), recording) {
+        match recording.save(path) {
+            Ok(()) => println!("Saved {} ticks of input to {}", recording.inputs.len(), path),
+            Err(e) => eprintln!("{}", e),
+        }
     }
 }
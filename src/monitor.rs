@@ -0,0 +1,190 @@
+use gamefox::physical::Vector2;
+use crate::behavior::{EntityBehavior, EntityContext, GameEvent};
+use crate::entity::Entity;
+use crate::flags::{choice_names, EntityFlags, FlagSpec, FlagType};
+use crate::game::StateHasher;
+use crate::player::{Player, Side};
+use std::fmt;
+use std::hash::Hash;
+
+/// What's inside, for both the monitor and the icon that comes out of it
+pub const FLAGS: &[FlagSpec] = &[FlagSpec { name: "item", kind: FlagType::Choice(&ITEM_NAMES), default: Some("rings") }];
+
+/// The name of each item in the flag, which is also what gets passed on to the icon
+const ITEMS: [(&str, MonitorItem); 5] = [
+    ("rings", MonitorItem::Rings),
+    ("life", MonitorItem::ExtraLife),
+    ("shoes", MonitorItem::SpeedShoes),
+    ("invincibility", MonitorItem::Invincibility),
+    ("shield", MonitorItem::Shield),
+];
+const ITEM_NAMES: [&str; 5] = choice_names(&ITEMS);
+
+const EXPLOSION_ANIMATION: &str = "Explosion";
+/// The kind of entity that pops out of a broken monitor, with an animation for each item's icon
+const ICON_KIND: &str = "MONITORICON";
+/// Where the icon starts out, relative to the monitor, so that it lines up with the screen
+const ICON_OFFSET: (f64, f64) = (6.0, 6.0);
+
+const ICON_RISE_SPEED: f64 = 3.0;
+const ICON_RISE_DECELERATION: f64 = 0.09375;
+/// How long the icon stays up after its item has been given to the player
+const ICON_LINGER_TIME: u32 = 30;
+
+const RING_BONUS: u32 = 10;
+const SPEED_SHOES_TIME: u32 = 1200;
+const INVINCIBILITY_TIME: u32 = 1200;
+
+/// What a monitor gives the player, from the item flag of its entity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MonitorItem {
+    #[default]
+    Rings,
+    ExtraLife,
+    SpeedShoes,
+    Invincibility,
+    Shield,
+}
+
+impl fmt::Display for MonitorItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, _) = ITEMS.iter().find(|(_, item)| item == self).unwrap();
+        write!(f, "{}", name)
+    }
+}
+
+impl MonitorItem {
+    pub fn apply(self, player: &mut Player) {
+        match self {
+            MonitorItem::Rings => {
+                for _ in 0..RING_BONUS {
+                    player.collect_ring();
+                }
+            }
+            MonitorItem::ExtraLife => player.add_life(),
+            MonitorItem::SpeedShoes => player.give_speed_shoes(SPEED_SHOES_TIME),
+            MonitorItem::Invincibility => player.give_invincibility(INVINCIBILITY_TIME),
+            MonitorItem::Shield => player.give_shield(),
+        }
+    }
+
+    /// The image of the icon's animation for this item
+    fn icon(self) -> &'static str {
+        match self {
+            MonitorItem::Rings => "Ring",
+            MonitorItem::ExtraLife => "Lives",
+            MonitorItem::SpeedShoes => "IconShoes",
+            MonitorItem::Invincibility => "IconInvincibility",
+            MonitorItem::Shield => "IconShield",
+        }
+    }
+
+    fn from_flags(flags: &EntityFlags) -> MonitorItem {
+        flags.choice_of("item", &ITEMS)
+    }
+}
+
+/// A box that can be stood on, and that breaks open when jumped or rolled into,
+/// sending up an icon that gives the player its item
+#[derive(Debug, Default)]
+pub struct Monitor {
+    item: MonitorItem,
+    /// Ticks left in the explosion, once broken
+    explosion_left: Option<u32>,
+}
+
+impl EntityBehavior for Monitor {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        self.item = MonitorItem::from_flags(entity.flags());
+    }
+
+    fn update(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        match self.explosion_left {
+            Some(0) => entity.remove(),
+            Some(ticks) => self.explosion_left = Some(ticks - 1),
+            None => {}
+        }
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        if self.explosion_left.is_some() {
+            return;
+        }
+
+        // Only the sides are walls, since landing on top is taken care of by being solid
+        if !ctx.player.is_attacking() {
+            if let Some(hitbox) = entity.hitbox() {
+                let side = ctx.player.touched_side(hitbox);
+                if side == Side::Left || side == Side::Right {
+                    ctx.player.push_out_of(hitbox.left() as f64, hitbox.right() as f64);
+                }
+            }
+            return;
+        }
+
+        ctx.player.rebound();
+
+        let position = entity.position();
+        let icon_position = Vector2 { x: position.x + ICON_OFFSET.0, y: position.y + ICON_OFFSET.1 };
+        match Entity::new(icon_position, ICON_KIND.to_string(), vec![self.item.to_string()]) {
+            Ok(icon) => ctx.events.push(GameEvent::Spawn(icon)),
+            Err(e) => eprintln!("Warning: Could not spawn monitor icon: {}", e),
+        }
+
+        entity.set_animation(EXPLOSION_ANIMATION);
+        self.explosion_left = Some(entity.animation_length());
+    }
+
+    fn is_destroyed(&self) -> bool {
+        self.explosion_left.is_some()
+    }
+
+    /// Curling up goes right through the top, so that landing on it breaks it instead
+    fn is_solid(&self, player: &Player) -> bool {
+        self.explosion_left.is_none() && !player.is_attacking()
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.item.hash(hasher);
+        self.explosion_left.hash(hasher);
+    }
+}
+
+/// The icon that rises out of a broken monitor, giving the player its item once it stops
+#[derive(Debug, Default)]
+pub struct MonitorIcon {
+    item: MonitorItem,
+    rise_speed: f64,
+    /// Ticks left before disappearing, once the item has been given
+    linger_left: Option<u32>,
+}
+
+impl EntityBehavior for MonitorIcon {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        self.item = MonitorItem::from_flags(entity.flags());
+        self.rise_speed = ICON_RISE_SPEED;
+        entity.set_animation(self.item.icon());
+    }
+
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        match self.linger_left {
+            Some(0) => entity.remove(),
+            Some(ticks) => self.linger_left = Some(ticks - 1),
+            None if self.rise_speed <= 0.0 => {
+                entity.set_velocity(Vector2 { x: 0.0, y: 0.0 });
+                self.item.apply(ctx.player);
+                self.linger_left = Some(ICON_LINGER_TIME);
+            }
+            None => {
+                entity.set_velocity(Vector2 { x: 0.0, y: -self.rise_speed });
+                self.rise_speed -= ICON_RISE_DECELERATION;
+            }
+        }
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.item.hash(hasher);
+        hasher.write_f64(self.rise_speed);
+        self.linger_left.hash(hasher);
+    }
+}
@@ -0,0 +1,61 @@
+use gamefox::physical::Physical;
+use crate::behavior::{EntityBehavior, EntityContext};
+use crate::entity::Entity;
+use crate::flags::{choice_names, FlagSpec, FlagType};
+use crate::game::StateHasher;
+use std::hash::Hash;
+
+pub const FLAGS: &[FlagSpec] = &[
+    FlagSpec { name: "mode", kind: FlagType::Choice(&MODE_NAMES), default: None },
+];
+
+const MODES: [(&str, PathSwitchMode); 3] = [
+    ("u", PathSwitchMode::Under),
+    ("i", PathSwitchMode::Inside),
+    ("s", PathSwitchMode::Side),
+];
+const MODE_NAMES: [&str; 3] = choice_names(&MODES);
+
+/// Which collision path a path switch puts the player on, from the mode flag of its entity.
+///
+/// A loop's paths each have one side of it solid: path 0 runs under the near side
+/// and up the far side, and path 1 comes back down the near side.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PathSwitchMode {
+    /// Always path 0, e.g. going into a loop
+    #[default]
+    Under,
+    /// Always path 1, e.g. at the top of a loop
+    Inside,
+    /// Path 0 on the right half of the switch and path 1 on the left,
+    /// so both leaving a loop and running back into it end up on the right path
+    Side,
+}
+
+/// Moves the player onto another collision path while they touch it
+#[derive(Debug, Default)]
+pub struct PathSwitch {
+    mode: PathSwitchMode,
+}
+
+impl EntityBehavior for PathSwitch {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        self.mode = entity.flags().choice_of("mode", &MODES);
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        let layer = match self.mode {
+            PathSwitchMode::Under => 0,
+            PathSwitchMode::Inside => 1,
+            PathSwitchMode::Side => {
+                let center = entity.hitbox().map(|h| h.center().x() as f64).unwrap_or(entity.position().x);
+                if ctx.player.get_position().x >= center { 0 } else { 1 }
+            }
+        };
+        ctx.player.set_layer(layer);
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.mode.hash(hasher);
+    }
+}
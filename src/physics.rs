@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use serde_json::de::from_reader;
+use std::collections::HashMap;
+use std::fs::File;
+
+const PROFILE_PATH: &str = "./assets/PhysicsProfiles.json";
+
+/// Every tunable number that affects how the player moves.
+///
+/// Speeds are in pixels per tick, and accelerations in pixels per tick per tick.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PhysicsProfile {
+    pub gravity: f64,
+    pub max_fall_speed: f64,
+    pub jump_velocity: f64,
+
+    /// Half-width and half-height of the player's hitbox while standing
+    pub standing_radius: (f64, f64),
+    /// Half-width and half-height of the player's hitbox while curled into a ball
+    pub rolling_radius: (f64, f64),
+    /// How far below the player the ground can be while still sticking to it
+    pub ground_snap_distance: f64,
+
+    pub acceleration: f64,
+    pub friction: f64,
+    /// Ground speed above which the run cycle is shown instead of walking
+    pub run_speed: f64,
+    /// How many times faster the player speeds up with speed shoes on
+    pub speed_shoes_factor: f64,
+
+    pub roll_friction: f64,
+    pub roll_deceleration: f64,
+    /// Minimum speed needed to start a roll by pressing down
+    pub roll_start_speed: f64,
+    /// Below this speed a roll uncurls back into running
+    pub roll_stop_speed: f64,
+
+    pub spindash_base_speed: f64,
+    /// Extra launch speed for each point of spindash charge
+    pub spindash_charge_speed: f64,
+    /// Charge added every time jump is pressed while crouching
+    pub spindash_charge_per_press: f64,
+    pub spindash_max_charge: f64,
+
+    pub fly_gravity: f64,
+    /// Upward speed gained each time jump is pressed while flying
+    pub fly_lift: f64,
+    pub fly_max_rise_speed: f64,
+    pub fly_max_fall_speed: f64,
+    /// Number of ticks Tails can fly before getting tired
+    pub fly_time: u32,
+
+    pub hurt_gravity: f64,
+    pub hurt_velocity_x: f64,
+    pub hurt_velocity_y: f64,
+    pub death_velocity: f64,
+    /// How long the player blinks and can't be hurt after landing from a hit
+    pub invulnerable_time: u32,
+    /// How long the player falls after dying before respawning
+    pub death_time: u32,
+
+    /// How fast rings get thrown out when the player gets hit
+    pub ring_scatter_speed: f64,
+    pub ring_gravity: f64,
+
+    pub yellow_spring_speed: f64,
+    pub red_spring_speed: f64,
+    /// How long left and right are ignored after bouncing off a sideways spring
+    pub spring_control_lock: u32,
+}
+
+impl PhysicsProfile {
+    /// Loads the preset with the given name (e.g. `classic` or `floaty`) from the profile file
+    pub fn load(name: &str) -> Result<PhysicsProfile, String> {
+        let file = File::open(PROFILE_PATH).map_err(|e| format!("Could not open {}: {}", PROFILE_PATH, e))?;
+        let mut profiles: HashMap<String, PhysicsProfile> = from_reader(file)
+            .map_err(|e| format!("Invalid physics profiles: {}", e))?;
+
+        profiles.remove(name).ok_or_else(|| {
+            let mut names = profiles.keys().cloned().collect::<Vec<String>>();
+            names.sort();
+            format!("Unknown physics profile {:?}, expected one of {}", name, names.join(", "))
+        })
+    }
+}
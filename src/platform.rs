@@ -0,0 +1,142 @@
+use gamefox::physical::Vector2;
+use crate::behavior::{EntityBehavior, EntityContext};
+use crate::entity::Entity;
+use crate::flags::{choice_names, FlagSpec, FlagType};
+use crate::game::StateHasher;
+use crate::player::Player;
+use std::f64::consts::PI;
+use std::hash::{Hash, Hasher};
+
+pub const FLAGS: &[FlagSpec] = &[
+    FlagSpec {
+        name: "motion",
+        kind: FlagType::Choice(&MOTION_NAMES),
+        default: Some("s"),
+    },
+    FlagSpec { name: "distance", kind: FlagType::Number, default: Some("64") },
+    FlagSpec { name: "period", kind: FlagType::Integer { min: 1, max: i64::MAX }, default: Some("180") },
+];
+
+/// Every way the motion flag can be written
+const MOTIONS: [(&str, PlatformMotion); 10] = [
+    ("s", PlatformMotion::Still),
+    ("still", PlatformMotion::Still),
+    ("h", PlatformMotion::Horizontal),
+    ("horizontal", PlatformMotion::Horizontal),
+    ("v", PlatformMotion::Vertical),
+    ("vertical", PlatformMotion::Vertical),
+    ("c", PlatformMotion::Circular),
+    ("circular", PlatformMotion::Circular),
+    ("f", PlatformMotion::Falling),
+    ("falling", PlatformMotion::Falling),
+];
+const MOTION_NAMES: [&str; 10] = choice_names(&MOTIONS);
+
+/// How long a falling platform holds on after first being stood on
+const FALL_DELAY: u32 = 30;
+
+/// How a platform moves, from the motion flag of its entity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PlatformMotion {
+    #[default]
+    Still,
+    /// Back and forth, left and right of where it was placed
+    Horizontal,
+    /// Up and down, above and below where it was placed
+    Vertical,
+    /// Around in a circle, centered on where it was placed
+    Circular,
+    /// Drops shortly after the player stands on it
+    Falling,
+}
+
+/// Something solid the player can stand on and get carried along by.
+///
+/// The flags are the motion, then how far it moves from where it was placed
+/// and how many ticks one full cycle takes, e.g. `h 64 180`.
+/// Anything left out uses the default.
+#[derive(Debug, Default)]
+pub struct Platform {
+    motion: PlatformMotion,
+    distance: f64,
+    period: u32,
+    /// Where the platform was placed, which it moves relative to
+    origin: (f64, f64),
+    /// Ticks into the current cycle
+    timer: u32,
+    /// Ticks left before dropping, once a falling platform has been stood on
+    fall_delay: Option<u32>,
+}
+
+impl Platform {
+    /// Where in its cycle the platform is, relative to where it was placed
+    fn offset(&self) -> (f64, f64) {
+        let angle = 2.0 * PI * self.timer as f64 / self.period as f64;
+        match self.motion {
+            PlatformMotion::Horizontal => (self.distance * angle.sin(), 0.0),
+            PlatformMotion::Vertical => (0.0, self.distance * angle.sin()),
+            PlatformMotion::Circular => (self.distance * angle.cos(), self.distance * angle.sin()),
+            PlatformMotion::Still | PlatformMotion::Falling => (0.0, 0.0),
+        }
+    }
+}
+
+impl EntityBehavior for Platform {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        let flags = entity.flags();
+        self.motion = flags.choice_of("motion", &MOTIONS);
+        self.distance = flags.number("distance");
+        self.period = flags.integer("period") as u32;
+
+        let position = entity.position();
+        self.origin = (position.x, position.y);
+        // Circling platforms start out off to the side
+        let (offset_x, offset_y) = self.offset();
+        entity.set_position(Vector2 { x: position.x + offset_x, y: position.y + offset_y });
+    }
+
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        if self.motion == PlatformMotion::Falling {
+            let stood_on = entity.top().filter(|top| ctx.player.is_standing_on(top)).is_some();
+            match self.fall_delay {
+                None if stood_on => self.fall_delay = Some(FALL_DELAY),
+                None => {}
+                Some(0) => {
+                    // Falls the same way the player does
+                    let velocity = entity.velocity();
+                    let gravity = ctx.player.physics().gravity;
+                    entity.set_velocity(Vector2 { x: velocity.x, y: velocity.y + gravity });
+                    if entity.position().y > ctx.terrain.pixel_height() as f64 {
+                        entity.remove();
+                    }
+                }
+                Some(ticks) => self.fall_delay = Some(ticks - 1),
+            }
+            return;
+        }
+
+        // The velocity is however far it takes to get to the next point in the cycle,
+        // so that whatever is standing on the platform knows how far to get carried
+        self.timer = (self.timer + 1) % self.period.max(1);
+        let (offset_x, offset_y) = self.offset();
+        let position = entity.position();
+        entity.set_velocity(Vector2 {
+            x: self.origin.0 + offset_x - position.x,
+            y: self.origin.1 + offset_y - position.y,
+        });
+    }
+
+    fn is_solid(&self, _player: &Player) -> bool {
+        true
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.motion.hash(hasher);
+        hasher.write_f64(self.distance);
+        hasher.write_u32(self.period);
+        hasher.write_f64(self.origin.0);
+        hasher.write_f64(self.origin.1);
+        hasher.write_u32(self.timer);
+        self.fall_delay.hash(hasher);
+    }
+}
@@ -1,65 +1,712 @@
-use gamefox::animation::Animation;
 use gamefox::physical::{Physical, Vector2};
 use gamefox::renderable::Renderable;
-use gamefox::input;
 use gamefox::camera::Camera;
+use crate::animation::Animation;
 use crate::block::TerrainGetter;
-use sdl2::keyboard::Keycode;
+use crate::controls::{Buttons, Input};
+use crate::game::StateHasher;
+use crate::physics::PhysicsProfile;
+use sdl2::rect::Rect;
 use sdl2::render::{Canvas, RenderTarget};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PlayerState {
+    /// Standing, walking or running along the ground
+    Ground,
+    /// Ducking down while standing still
+    Crouching,
+    /// Revving up in place, ready to launch into a roll
+    Spindash,
+    /// Curled up into a ball and rolling along the ground
+    Rolling,
+    /// Curled up in the air after a jump
+    Jumping,
+    /// Falling without having jumped, but not flying yet
+    Airborne,
+    /// Flying, with the given number of ticks left before Tails gets tired
+    Flying(u32),
+    /// Flight time has run out, so Tails slowly drifts down until landing
+    FlyTired,
+    /// Knocked back after getting hit, without any control until landing
+    Hurt,
+    /// Falling off the screen, with the given number of ticks left before respawning
+    Dead(u32),
+    /// Died without any lives left, so there's no respawning
+    GameOver,
+}
 
 pub struct Player {
     position: Vector2,
+    /// Where the player was before the last tick, for smoothing out rendering
+    previous_position: Vector2,
     velocity: Vector2,
+    physics: PhysicsProfile,
+
+    state: PlayerState,
+    facing_left: bool,
+    spindash_charge: f64,
+    /// Which of the two collision paths the player is on, which path switches change
+    layer: usize,
+    /// Ticks left before left and right do anything again
+    control_lock: u32,
+    /// How far the platform the player is standing on moved this tick, which jumping off it adds on
+    platform_velocity: Option<Vector2>,
+
+    rings: u32,
+    lives: u32,
+    score: u32,
+    /// How many extra lives collecting rings has given since respawning
+    ring_lives: u32,
+    /// Ticks left before the player can get hurt again
+    invulnerable: u32,
+    /// Ticks left of faster acceleration from speed shoes
+    speed_shoes: u32,
+    /// Ticks left of being able to run through anything, from an invincibility monitor
+    invincible: u32,
+    /// Takes the next hit instead of the player losing their rings
+    shield: bool,
+    /// Strikes the act-clear pose once standing still
+    celebrating: bool,
+    /// Where the player comes back after dying
+    checkpoint: Vector2,
+
+    animations: HashMap<&'static str, Animation>,
+    current_animation: &'static str,
+}
 
-    // TODO: Multiple animations
-    animation: Animation,
+/// Sprite sheets of every animation the player uses,
+/// with the frame layout coming from each one's metadata file
+const ANIMATIONS: &[&str] = &[
+    "Tails/Idle",
+    "Tails/Walk",
+    "Tails/Run",
+    "Tails/Fly",
+    "Tails/Fly_Tired",
+    "Tails/Crouch",
+    "Tails/Spindash",
+    "Tails/Roll_Body",
+    "Tails/Roll_Tails",
+    "Tails/Hurt",
+    "Tails/Act_Clear",
+];
+
+/// The walk and run cycles play at their normal rate at this fraction of the run speed
+const ANIMATION_REFERENCE_FRACTION: f64 = 2.0 / 3.0;
+const MIN_ANIMATION_SPEED: f64 = 0.5;
+
+const STARTING_LIVES: u32 = 3;
+/// Every time the ring count reaches another multiple of this, the player gets an extra life
+pub const RINGS_PER_LIFE: u32 = 100;
+
+/// How far above the player's feet the sprite origins are measured from, standing and curled up,
+/// which depends on the sprites rather than the hitbox of any physics profile
+const SPRITE_HEIGHT: f64 = 20.0;
+const CURLED_SPRITE_HEIGHT: f64 = 14.0;
+
+/// The top of something solid that isn't part of the terrain, like a platform.
+/// The player can land on it from above, but passes right through it from below or the side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolidTop {
+    pub left: f64,
+    pub right: f64,
+    pub y: f64,
+    /// How far it moved during the current tick
+    pub velocity: Vector2,
 }
 
-const MAX_FALL_SPEED: f64 = /*16.0*/ 1.0;
-const GRAVITY: f64 = /*0.21875*/ 0.02;
+impl SolidTop {
+    /// Where the top was before it moved this tick
+    pub fn before_moving(&self) -> SolidTop {
+        SolidTop {
+            left: self.left - self.velocity.x,
+            right: self.right - self.velocity.x,
+            y: self.y - self.velocity.y,
+            velocity: self.velocity,
+        }
+    }
+
+    fn overlaps(&self, x: f64, x_radius: f64) -> bool {
+        self.left <= x + x_radius && self.right >= x - x_radius
+    }
+}
+
+/// A side of something solid that the player can run into
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
 
 impl Player {
-    pub fn new() -> Player {
+    pub fn new(physics: PhysicsProfile) -> Player {
+        let animations = ANIMATIONS
+            .iter()
+            .map(|&name| (name, Animation::load(name).unwrap()))
+            .collect();
+
         Player {
             position: Vector2 { x: 0.0, y: 0.0 },
+            previous_position: Vector2 { x: 0.0, y: 0.0 },
             velocity: Vector2 { x: 0.5, y: 0.5 },
-            animation: Animation::new("Tornado".to_string(), 4, Duration::from_millis(50)),
+            physics,
+            state: PlayerState::Airborne,
+            facing_left: false,
+            spindash_charge: 0.0,
+            layer: 0,
+            control_lock: 0,
+            platform_velocity: None,
+            rings: 0,
+            lives: STARTING_LIVES,
+            score: 0,
+            ring_lives: 0,
+            invulnerable: 0,
+            speed_shoes: 0,
+            invincible: 0,
+            shield: false,
+            celebrating: false,
+            checkpoint: Vector2 { x: 0.0, y: 0.0 },
+            animations,
+            current_animation: "Tails/Idle",
+        }
+    }
+
+    pub fn state(&self) -> PlayerState {
+        self.state
+    }
+
+    pub fn velocity(&self) -> Vector2 {
+        self.velocity
+    }
+
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    pub fn set_layer(&mut self, layer: usize) {
+        self.layer = layer;
+    }
+
+    pub fn physics(&self) -> &PhysicsProfile {
+        &self.physics
+    }
+
+    /// Moves the player straight to the given position, without anything in between
+    pub fn set_position(&mut self, position: Vector2) {
+        self.position = position;
+        self.previous_position = position;
+    }
+
+    pub fn rings(&self) -> u32 {
+        self.rings
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn add_score(&mut self, points: u32) {
+        self.score += points;
+    }
+
+    /// Whether the player is curled up or invincible,
+    /// so that touching a badnik destroys it instead of getting hurt
+    pub fn is_attacking(&self) -> bool {
+        self.is_curled() || self.state == PlayerState::Spindash || self.invincible > 0
+    }
+
+    pub fn add_life(&mut self) {
+        self.lives += 1;
+    }
+
+    pub fn give_speed_shoes(&mut self, ticks: u32) {
+        self.speed_shoes = ticks;
+    }
+
+    pub fn give_invincibility(&mut self, ticks: u32) {
+        self.invincible = ticks;
+    }
+
+    pub fn give_shield(&mut self) {
+        self.shield = true;
+    }
+
+    pub fn has_shield(&self) -> bool {
+        self.shield
+    }
+
+    /// Strikes the act-clear pose as soon as the player is standing still on the ground
+    pub fn celebrate(&mut self) {
+        self.celebrating = true;
+    }
+
+    /// Gets ready to play another act, back at the start of it but keeping the score and lives
+    pub fn start_act(&mut self) {
+        self.checkpoint = Vector2 { x: 0.0, y: 0.0 };
+        self.respawn();
+    }
+
+    /// Which side of `solid` the player came into it from,
+    /// going by whichever way they overlap it the least
+    pub fn touched_side(&self, solid: Rect) -> Side {
+        let player = self.hitbox();
+        let overlap_x = solid.right().min(player.right()) - solid.left().max(player.left());
+        let overlap_y = solid.bottom().min(player.bottom()) - solid.top().max(player.top());
+
+        if overlap_x >= overlap_y {
+            if player.center().y() < solid.center().y() { Side::Top } else { Side::Bottom }
+        } else if player.center().x() < solid.center().x() {
+            Side::Left
+        } else {
+            Side::Right
+        }
+    }
+
+    /// Pushes the player out the side of something solid spanning from `left` to `right`,
+    /// stopping them from moving any further into it
+    pub fn push_out_of(&mut self, left: f64, right: f64) {
+        let (x_radius, _) = self.radius();
+        if self.position.x < (left + right) / 2.0 {
+            self.position.x = left - x_radius;
+            self.velocity.x = self.velocity.x.min(0.0);
+        } else {
+            self.position.x = right + x_radius;
+            self.velocity.x = self.velocity.x.max(0.0);
+        }
+    }
+
+    /// Pushes the player out the bottom of something solid, stopping them from moving up into it
+    pub fn push_below(&mut self, bottom: f64) {
+        let (_, y_radius) = self.radius();
+        self.position.y = bottom + 2.0 * y_radius;
+        self.velocity.y = self.velocity.y.max(0.0);
+    }
+
+    /// Bounces back up after landing on top of something that got destroyed
+    pub fn rebound(&mut self) {
+        if !self.is_grounded() && self.velocity.y > 0.0 {
+            self.velocity.y = -self.velocity.y;
+        }
+    }
+
+    pub fn collect_ring(&mut self) {
+        self.rings += 1;
+        if self.rings >= RINGS_PER_LIFE * (self.ring_lives + 1) {
+            self.ring_lives += 1;
+            self.lives += 1;
+        }
+    }
+
+    /// Sets where the player respawns after dying, instead of the start of the act
+    pub fn set_checkpoint(&mut self, position: Vector2) {
+        self.checkpoint = position;
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable > 0 || self.invincible > 0 || self.state == PlayerState::Hurt || self.is_dead()
+    }
+
+    /// Hits the player from something at the given x position,
+    /// knocking them away from it and returning how many rings they lost.
+    /// Without any rings to lose, the player dies instead.
+    pub fn hurt(&mut self, source_x: f64) -> u32 {
+        if self.is_invulnerable() {
+            return 0;
+        }
+
+        let lost = if self.shield {
+            self.shield = false;
+            0
+        } else if self.rings == 0 {
+            self.die();
+            return 0;
+        } else {
+            std::mem::replace(&mut self.rings, 0)
+        };
+
+        let away = if self.position.x < source_x { -1.0 } else { 1.0 };
+        self.velocity = Vector2 { x: away * self.physics.hurt_velocity_x, y: self.physics.hurt_velocity_y };
+        self.state = PlayerState::Hurt;
+
+        lost
+    }
+
+    /// Flings the player off a spring, replacing whichever parts of the velocity are given.
+    /// Anything with an upwards or downwards push sends the player into the air.
+    pub fn launch(&mut self, x: Option<f64>, y: Option<f64>, control_lock: u32) {
+        if self.is_dead() {
+            return;
+        }
+
+        if let Some(x) = x {
+            self.velocity.x = x;
+            self.facing_left = x < 0.0;
+        }
+
+        if let Some(y) = y {
+            self.velocity.y = y;
+            self.state = PlayerState::Airborne;
+        }
+
+        self.control_lock = control_lock;
+    }
+
+    /// Whether the player is dead, whether or not they're coming back
+    pub fn is_dead(&self) -> bool {
+        matches!(self.state, PlayerState::Dead(_) | PlayerState::GameOver)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.state == PlayerState::GameOver
+    }
+
+    pub fn die(&mut self) {
+        if self.is_dead() {
+            return;
         }
+
+        // Only ever reaches 0 on this death, which ends the game instead of respawning
+        self.lives -= 1;
+        self.velocity = Vector2 { x: 0.0, y: -self.physics.death_velocity };
+        self.state = PlayerState::Dead(self.physics.death_time);
+    }
+
+    fn respawn(&mut self) {
+        self.position = self.checkpoint;
+        self.previous_position = self.checkpoint;
+        self.velocity = Vector2 { x: 0.0, y: 0.0 };
+        self.state = PlayerState::Airborne;
+        self.layer = 0;
+        self.rings = 0;
+        self.ring_lives = 0;
+        self.invulnerable = 0;
+        self.speed_shoes = 0;
+        self.invincible = 0;
+        self.shield = false;
+        self.celebrating = false;
+        self.control_lock = 0;
+        self.platform_velocity = None;
+    }
+
+    /// Whether the player is on the ground, right on top of the given surface
+    pub fn is_standing_on(&self, top: &SolidTop) -> bool {
+        let (x_radius, _) = self.radius();
+        self.is_grounded() && top.overlaps(self.position.x, x_radius) && (self.position.y - top.y).abs() < 1.0
     }
 
-    pub fn update(&mut self, getter: &TerrainGetter) {
+    /// Simulates one tick, with `solids` being everything other than the terrain that can be stood on
+    pub fn update(&mut self, getter: &TerrainGetter, input: Input, solids: &[SolidTop]) {
+        self.previous_position = self.position;
+        if self.is_game_over() {
+            return;
+        }
+
+        // Anything stood on at the end of the last tick carries the player along with it
+        self.platform_velocity = solids
+            .iter()
+            .find(|top| self.is_standing_on(&top.before_moving()))
+            .map(|top| top.velocity);
+        if let Some(carried) = self.platform_velocity {
+            self.position.x += carried.x;
+            self.position.y += carried.y;
+        }
+
         self.position.x += self.velocity.x;
         self.position.y += self.velocity.y;
 
-        self.velocity.y += GRAVITY;
-        if self.velocity.y >= MAX_FALL_SPEED {
-            self.velocity.y = MAX_FALL_SPEED;
+        if let PlayerState::Dead(time_left) = self.state {
+            self.apply_gravity(self.physics.gravity, self.physics.max_fall_speed);
+            self.update_animation();
+            if time_left > 0 {
+                self.state = PlayerState::Dead(time_left - 1);
+            } else if self.lives == 0 {
+                self.state = PlayerState::GameOver;
+            } else {
+                self.respawn();
+            }
+            return;
         }
 
-        if input::key_held(Keycode::D) {
-            self.velocity.x += 0.05;
-        } else if input::key_held(Keycode::A) {
-            self.velocity.x -= 0.05;
-        } else if self.velocity.x.abs() >= 0.02 {
-            self.velocity.x -= self.velocity.x.signum() * 0.02;
+        if self.position.y > getter.pixel_height() as f64 {
+            self.die();
+            return;
+        }
+
+        if self.invulnerable > 0 && self.state != PlayerState::Hurt {
+            self.invulnerable -= 1;
+        }
+        self.speed_shoes = self.speed_shoes.saturating_sub(1);
+        self.invincible = self.invincible.saturating_sub(1);
+        let acceleration = if self.speed_shoes > 0 {
+            self.physics.speed_shoes_factor * self.physics.acceleration
         } else {
-            self.velocity.x = 0.0;
+            self.physics.acceleration
+        };
+
+        let controls_locked = self.control_lock > 0;
+        self.control_lock = self.control_lock.saturating_sub(1);
+
+        let left_held = input.held(Buttons::LEFT);
+        let right_held = input.held(Buttons::RIGHT);
+        let down_held = input.held(Buttons::DOWN);
+        let jump_pressed = input.pressed(Buttons::JUMP);
+
+        match self.state {
+            PlayerState::Hurt | PlayerState::Dead(_) | PlayerState::GameOver => {}
+            PlayerState::Crouching | PlayerState::Spindash => {
+                self.velocity.x = 0.0;
+            }
+            PlayerState::Rolling => {
+                if (right_held && self.velocity.x < 0.0) || (left_held && self.velocity.x > 0.0) {
+                    self.velocity.x -= self.velocity.x.signum() * self.physics.roll_deceleration;
+                }
+                self.apply_friction(self.physics.roll_friction);
+            }
+            _ if controls_locked => {
+                self.apply_friction(self.physics.friction);
+            }
+            _ => {
+                if right_held {
+                    self.velocity.x += acceleration;
+                    self.facing_left = false;
+                } else if left_held {
+                    self.velocity.x -= acceleration;
+                    self.facing_left = true;
+                } else {
+                    self.apply_friction(self.physics.friction);
+                }
+            }
         }
 
-        if input::key_pressed(Keycode::Space) {
-            self.velocity.y -= 0.4;
+        match self.state {
+            PlayerState::Ground => {
+                self.apply_gravity(self.physics.gravity, self.physics.max_fall_speed);
+                if jump_pressed {
+                    self.jump();
+                } else if down_held && self.velocity.x.abs() >= self.physics.roll_start_speed {
+                    self.state = PlayerState::Rolling;
+                } else if down_held && self.velocity.x == 0.0 {
+                    self.state = PlayerState::Crouching;
+                }
+            }
+            PlayerState::Crouching => {
+                self.apply_gravity(self.physics.gravity, self.physics.max_fall_speed);
+                if !down_held {
+                    self.state = PlayerState::Ground;
+                } else if jump_pressed {
+                    self.spindash_charge = self.physics.spindash_charge_per_press;
+                    self.state = PlayerState::Spindash;
+                }
+            }
+            PlayerState::Spindash => {
+                self.apply_gravity(self.physics.gravity, self.physics.max_fall_speed);
+                if !down_held {
+                    let speed = self.physics.spindash_base_speed + self.spindash_charge.floor() * self.physics.spindash_charge_speed;
+                    self.velocity.x = if self.facing_left { -speed } else { speed };
+                    self.spindash_charge = 0.0;
+                    self.state = PlayerState::Rolling;
+                } else {
+                    // The charge slowly bleeds away unless jump keeps getting pressed
+                    self.spindash_charge -= self.spindash_charge / 32.0;
+                    if jump_pressed {
+                        self.spindash_charge = (self.spindash_charge + self.physics.spindash_charge_per_press).min(self.physics.spindash_max_charge);
+                    }
+                }
+            }
+            PlayerState::Rolling => {
+                self.apply_gravity(self.physics.gravity, self.physics.max_fall_speed);
+                if jump_pressed {
+                    self.jump();
+                } else if self.velocity.x.abs() < self.physics.roll_stop_speed {
+                    self.state = PlayerState::Ground;
+                }
+            }
+            PlayerState::Jumping | PlayerState::Airborne => {
+                self.apply_gravity(self.physics.gravity, self.physics.max_fall_speed);
+                if jump_pressed {
+                    self.state = PlayerState::Flying(self.physics.fly_time);
+                }
+            }
+            PlayerState::Flying(time_left) => {
+                self.apply_gravity(self.physics.fly_gravity, self.physics.fly_max_fall_speed);
+                if jump_pressed {
+                    self.velocity.y = (self.velocity.y - self.physics.fly_lift).max(-self.physics.fly_max_rise_speed);
+                }
+
+                self.state = if time_left == 0 {
+                    PlayerState::FlyTired
+                } else {
+                    PlayerState::Flying(time_left - 1)
+                };
+            }
+            PlayerState::FlyTired => {
+                self.apply_gravity(self.physics.fly_gravity, self.physics.fly_max_fall_speed);
+            }
+            PlayerState::Hurt => {
+                self.apply_gravity(self.physics.hurt_gravity, self.physics.max_fall_speed);
+            }
+            PlayerState::Dead(_) | PlayerState::GameOver => unreachable!(),
         }
 
-        self.animation.update();
+        self.update_animation();
 
-        let (ground, _angle) = find_ground_height(self.get_position(), 5.0, 10.0, getter).map(|(g, a)| (g as f64, a)).unwrap_or((std::f64::INFINITY, 0));
+        let (x_radius, y_radius) = self.radius();
+        let (mut ground, _angle) = find_ground_height(self.get_position(), x_radius, y_radius, self.layer, getter).map(|(g, a)| (g as f64, a)).unwrap_or((std::f64::INFINITY, 0));
+        // Solid tops only count if the player was above them before this tick
+        for top in solids.iter().filter(|top| top.overlaps(self.position.x, x_radius)) {
+            if self.previous_position.y <= top.before_moving().y + 1.0 && top.y < ground {
+                ground = top.y;
+            }
+        }
 
-        if self.position.y >= ground {
+        let snap_to_ground = self.is_grounded() && ground - self.position.y <= self.physics.ground_snap_distance;
+        // Moving upwards never lands, or else a jump would be cancelled on the tick it starts
+        let landing = self.position.y >= ground && self.velocity.y >= 0.0;
+        if landing || snap_to_ground {
             self.position.y = ground;
             if self.velocity.y > 0.0 {
                 self.velocity.y = 0.0;
             }
+            if self.state == PlayerState::Hurt {
+                self.velocity.x = 0.0;
+                self.invulnerable = self.physics.invulnerable_time;
+            }
+            if !self.is_grounded() {
+                self.state = PlayerState::Ground;
+            }
+        } else if self.state == PlayerState::Rolling {
+            self.state = PlayerState::Jumping;
+        } else if self.is_grounded() {
+            self.state = PlayerState::Airborne;
+        }
+    }
+
+    fn jump(&mut self) {
+        self.velocity.y = -self.physics.jump_velocity;
+        if let Some(carried) = self.platform_velocity.take() {
+            self.velocity.x += carried.x;
+            self.velocity.y += carried.y;
+        }
+        self.state = PlayerState::Jumping;
+    }
+
+    fn apply_friction(&mut self, friction: f64) {
+        if self.velocity.x.abs() >= friction {
+            self.velocity.x -= self.velocity.x.signum() * friction;
+        } else {
+            self.velocity.x = 0.0;
+        }
+    }
+
+    fn is_grounded(&self) -> bool {
+        match self.state {
+            PlayerState::Ground | PlayerState::Crouching | PlayerState::Spindash | PlayerState::Rolling => true,
+            PlayerState::Jumping | PlayerState::Airborne | PlayerState::Flying(_) | PlayerState::FlyTired => false,
+            PlayerState::Hurt | PlayerState::Dead(_) | PlayerState::GameOver => false,
+        }
+    }
+
+    /// Whether the player is curled into a ball, with the tails drawn separately
+    fn is_curled(&self) -> bool {
+        self.state == PlayerState::Rolling || self.state == PlayerState::Jumping
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_f64(self.position.x);
+        hasher.write_f64(self.position.y);
+        hasher.write_f64(self.velocity.x);
+        hasher.write_f64(self.velocity.y);
+        self.state.hash(hasher);
+        self.facing_left.hash(hasher);
+        hasher.write_f64(self.spindash_charge);
+        hasher.write_usize(self.layer);
+        hasher.write_u32(self.control_lock);
+        self.platform_velocity.is_some().hash(hasher);
+        if let Some(carried) = self.platform_velocity {
+            hasher.write_f64(carried.x);
+            hasher.write_f64(carried.y);
+        }
+        hasher.write_u32(self.rings);
+        hasher.write_u32(self.lives);
+        hasher.write_u32(self.score);
+        hasher.write_u32(self.ring_lives);
+        hasher.write_u32(self.invulnerable);
+        hasher.write_u32(self.speed_shoes);
+        hasher.write_u32(self.invincible);
+        self.shield.hash(hasher);
+        self.celebrating.hash(hasher);
+        self.current_animation.hash(hasher);
+        self.animations[self.current_animation].hash_state(hasher);
+        // The tails behind the ball animate on their own while curled up
+        self.animations["Tails/Roll_Tails"].hash_state(hasher);
+    }
+
+    pub fn hitbox(&self) -> Rect {
+        let (x_radius, y_radius) = self.radius();
+        Rect::new(
+            (self.position.x - x_radius) as i32,
+            (self.position.y - 2.0 * y_radius) as i32,
+            (2.0 * x_radius) as u32,
+            (2.0 * y_radius) as u32,
+        )
+    }
+
+    fn radius(&self) -> (f64, f64) {
+        if self.is_curled() {
+            self.physics.rolling_radius
+        } else {
+            self.physics.standing_radius
+        }
+    }
+
+    fn apply_gravity(&mut self, gravity: f64, max_fall_speed: f64) {
+        self.velocity.y += gravity;
+        if self.velocity.y >= max_fall_speed {
+            self.velocity.y = max_fall_speed;
+        }
+    }
+
+    fn update_animation(&mut self) {
+        let name = self.animation_name();
+        if name != self.current_animation {
+            self.animations.get_mut(name).unwrap().restart();
+            self.current_animation = name;
+        }
+
+        let speed = if name == "Tails/Walk" || name == "Tails/Run" {
+            (self.velocity.x.abs() / (self.physics.run_speed * ANIMATION_REFERENCE_FRACTION)).max(MIN_ANIMATION_SPEED)
+        } else {
+            1.0
+        };
+
+        let animation = self.animations.get_mut(name).unwrap();
+        animation.set_speed(speed);
+        animation.update();
+
+        if self.is_curled() {
+            self.animations.get_mut("Tails/Roll_Tails").unwrap().update();
+        }
+    }
+
+    fn animation_name(&self) -> &'static str {
+        match self.state {
+            PlayerState::Ground if self.velocity.x == 0.0 && self.celebrating => "Tails/Act_Clear",
+            PlayerState::Ground if self.velocity.x == 0.0 => "Tails/Idle",
+            PlayerState::Ground if self.velocity.x.abs() >= self.physics.run_speed => "Tails/Run",
+            PlayerState::Ground | PlayerState::Airborne => "Tails/Walk",
+            PlayerState::Crouching => "Tails/Crouch",
+            PlayerState::Spindash => "Tails/Spindash",
+            PlayerState::Rolling | PlayerState::Jumping => "Tails/Roll_Body",
+            PlayerState::Flying(_) => "Tails/Fly",
+            PlayerState::FlyTired => "Tails/Fly_Tired",
+            PlayerState::Hurt | PlayerState::Dead(_) | PlayerState::GameOver => "Tails/Hurt",
         }
     }
 }
@@ -70,37 +717,63 @@ impl Physical for Player {
     }
 }
 
+impl Player {
+    /// The player's position blended between the last two ticks,
+    /// where an `alpha` of 0 is the previous tick and 1 is the current one
+    pub fn interpolated_position(&self, alpha: f64) -> Vector2 {
+        Vector2 {
+            x: self.previous_position.x + (self.position.x - self.previous_position.x) * alpha,
+            y: self.previous_position.y + (self.position.y - self.previous_position.y) * alpha,
+        }
+    }
+
+    pub fn render_interpolated<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera, alpha: f64) -> Result<(), String> {
+        // Blink while invulnerable
+        if self.invulnerable > 0 && (self.invulnerable / 4) % 2 == 1 {
+            return Ok(());
+        }
+
+        // Sprite origins are relative to the top middle of the sprite's own height
+        let mut pos = self.interpolated_position(alpha);
+        pos.y -= if self.is_curled() { CURLED_SPRITE_HEIGHT } else { SPRITE_HEIGHT };
+
+        if self.is_curled() {
+            self.animations["Tails/Roll_Tails"].render(canvas, camera, pos, self.facing_left)?;
+        }
+
+        self.animations[self.current_animation].render(canvas, camera, pos, self.facing_left)
+    }
+}
+
 impl Renderable for Player {
     fn render<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera) -> Result<(), String> {
-        let mut pos = self.position;
-        pos.y -= self.animation.height() as f64;
-        (pos, &self.animation).render(canvas, camera)
+        self.render_interpolated(canvas, camera, 1.0)
     }
 }
 
 /// # Returns
 /// The y position and angle of the tile found,
 /// if any.
-pub fn find_ground_height(position: Vector2, x_radius: f64, _y_radius: f64, getter: &TerrainGetter) -> Option<(u32, u8)> {
+pub fn find_ground_height(position: Vector2, x_radius: f64, _y_radius: f64, layer: usize, getter: &TerrainGetter) -> Option<(u32, u8)> {
     let start_coord = (position.x - x_radius) as u32;
     let end_coord = (position.x + x_radius) as u32;
 
     let results: Vec<(u32, u8)> = (start_coord..=end_coord)
-        .filter_map(|c| collide_line((c as i32, position.y as i32), getter))
+        .filter_map(|c| collide_line((c as i32, position.y as i32), layer, getter))
         .collect();
 
     results.iter().min_by_key(|(height, _angle)| height).copied()
 }
 
-fn collide_line(position: (i32, i32), getter: &TerrainGetter) -> Option<(u32, u8)> {
+fn collide_line(position: (i32, i32), layer: usize, getter: &TerrainGetter) -> Option<(u32, u8)> {
     let mut result = None;
     let mut y = position.1 + 17;
-    while getter.is_occupied(position.0, y - 1, 0) || (result.is_none() && y >= position.1) {
+    while getter.is_occupied(position.0, y - 1, layer) || (result.is_none() && y >= position.1) {
         result = Some(y);
         y -= 1;
     }
 
-    let (data, angle) = getter.tile_at(position.0, result?, 0)?;
+    let (data, angle) = getter.tile_at(position.0, result?, layer)?;
     let top = y as u32 + 16 - data[(position.0 % 8) as usize];
     Some((top, angle))
-}
\ No newline at end of file
+}
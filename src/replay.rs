@@ -0,0 +1,138 @@
+use crate::controls::Buttons;
+use crate::physics::PhysicsProfile;
+use std::fmt;
+use std::str::FromStr;
+
+const REPLAY_VERSION: &str = "REPLAY 1";
+
+/// Everything needed to play a run back exactly as it happened:
+/// the act, the physics it was played with, the RNG seed,
+/// and the buttons that were held on every tick.
+///
+/// The file starts with a header of `key value` lines, followed by `E`
+/// and then a run-length encoded list of `<ticks> <buttons>` lines.
+/// The whole physics profile is stored rather than just its name,
+/// so that tuning the profiles afterwards doesn't break old replays.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub act: String,
+    pub physics_name: String,
+    pub physics: PhysicsProfile,
+    pub seed: u64,
+    pub inputs: Vec<Buttons>,
+}
+
+impl Replay {
+    pub fn new(act: String, physics_name: String, physics: PhysicsProfile, seed: u64) -> Replay {
+        Replay {
+            act,
+            physics_name,
+            physics,
+            seed,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// The buttons held on the given tick, or nothing once the replay has run out
+    pub fn input(&self, tick: u64) -> Buttons {
+        self.inputs.get(tick as usize).copied().unwrap_or(Buttons::NONE)
+    }
+
+    pub fn load(path: &str) -> Result<Replay, String> {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read replay {}: {}", path, e))?
+            .parse::<Replay>()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_string()).map_err(|e| format!("Could not write replay {}: {}", path, e))
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", REPLAY_VERSION)?;
+        writeln!(f, "act {}", self.act)?;
+        writeln!(f, "physics {}", self.physics_name)?;
+        writeln!(f, "profile {}", serde_json::to_string(&self.physics).map_err(|_| fmt::Error)?)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "E")?;
+
+        let mut inputs = self.inputs.iter().peekable();
+        while let Some(buttons) = inputs.next() {
+            let mut count = 1;
+            while inputs.peek() == Some(&buttons) {
+                inputs.next();
+                count += 1;
+            }
+            writeln!(f, "{} {:02x}", count, buttons.bits())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        if lines.next() != Some(REPLAY_VERSION) {
+            return Err(format!("Expected {:?}", REPLAY_VERSION));
+        }
+
+        let mut act = None;
+        let mut physics_name = None;
+        let mut physics = None;
+        let mut seed = None;
+
+        for line in lines.by_ref().take_while(|l| l != &"E") {
+            let mut parts = line.splitn(2, ' ');
+            let key = parts.next().unwrap();
+            let value = parts
+                .next()
+                .ok_or_else(|| format!("Expected a value for {}", key))?;
+
+            match key {
+                "act" => act = Some(value.to_string()),
+                "physics" => physics_name = Some(value.to_string()),
+                "profile" => {
+                    physics = Some(serde_json::from_str::<PhysicsProfile>(value).map_err(|e| format!("Invalid profile: {}", e))?)
+                }
+                "seed" => seed = Some(value.parse::<u64>().map_err(|e| format!("Parsing error: {}", e))?),
+                _ => return Err(format!("Unknown replay header {:?}", key)),
+            }
+        }
+
+        let mut inputs = Vec::new();
+        for line in lines {
+            let mut numbers = line.split(' ').filter(|s| !s.is_empty());
+
+            let count = numbers
+                .next()
+                .ok_or_else(|| "Expected tick count".to_string())?
+                .parse::<usize>()
+                .map_err(|e| format!("Parsing error: {}", e))?;
+
+            let buttons = numbers
+                .next()
+                .ok_or_else(|| "Expected buttons".to_string())?;
+            let buttons = u8::from_str_radix(buttons, 16).map_err(|e| format!("Parsing error: {}", e))?;
+
+            if numbers.next().is_some() {
+                return Err("Trailing data after input".to_string());
+            }
+
+            inputs.extend(std::iter::repeat(Buttons::from_bits(buttons)).take(count));
+        }
+
+        Ok(Replay {
+            act: act.ok_or_else(|| "Expected act".to_string())?,
+            physics_name: physics_name.ok_or_else(|| "Expected physics".to_string())?,
+            physics: physics.ok_or_else(|| "Expected profile".to_string())?,
+            seed: seed.ok_or_else(|| "Expected seed".to_string())?,
+            inputs,
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use crate::behavior::{EntityBehavior, EntityContext};
+use crate::entity::Entity;
+use crate::game::StateHasher;
+use std::hash::Hash;
+
+const SPARKLE_ANIMATION: &str = "RingSparkle";
+
+/// A ring that gets collected when touched, sparkling for a moment before disappearing
+#[derive(Debug, Default)]
+pub struct Ring {
+    /// Ticks left to sparkle for, once collected
+    sparkle_left: Option<u32>,
+}
+
+impl EntityBehavior for Ring {
+    fn update(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        match self.sparkle_left {
+            Some(0) => entity.remove(),
+            Some(ticks) => self.sparkle_left = Some(ticks - 1),
+            None => {}
+        }
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        if self.sparkle_left.is_some() {
+            return;
+        }
+
+        ctx.player.collect_ring();
+        entity.set_animation(SPARKLE_ANIMATION);
+        self.sparkle_left = Some(entity.animation_length());
+    }
+
+    fn is_destroyed(&self) -> bool {
+        self.sparkle_left.is_some()
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.sparkle_left.hash(hasher);
+    }
+}
@@ -0,0 +1,137 @@
+use gamefox::physical::{Physical, Vector2};
+use crate::act::ActFile;
+use crate::block::TerrainGetter;
+use crate::controls::Buttons;
+use crate::game::Game;
+use crate::physics::PhysicsProfile;
+use crate::player::Player;
+use crate::zone::Zone;
+use std::str::FromStr;
+
+/// An act loaded without any window or textures, played through with scripted inputs
+pub struct Scenario {
+    pub act: ActFile,
+    pub zone: Zone,
+    pub game: Game,
+}
+
+impl Scenario {
+    pub fn load(act_path: &str, physics_name: &str) -> Result<Scenario, String> {
+        let mut act = ActFile::load(act_path)?;
+
+        let zone = Zone::load(&act.zone)?;
+
+        let physics = PhysicsProfile::load(physics_name)?;
+        let entities = std::mem::take(&mut act.entities);
+        let game = Game::new(Player::new(physics), entities, 0);
+
+        Ok(Scenario {
+            act,
+            zone,
+            game,
+        })
+    }
+
+    /// Puts the player down at the given position before playing anything
+    pub fn start_at(mut self, x: f64, y: f64) -> Scenario {
+        self.game.player.set_position(Vector2 { x, y });
+        self
+    }
+
+    pub fn getter(&self) -> TerrainGetter<'_> {
+        self.zone.terrain(&self.act)
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.game.tick()
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.game.player
+    }
+
+    pub fn position(&self) -> Vector2 {
+        self.game.player.get_position()
+    }
+
+    /// Simulates the given number of ticks, holding down the same buttons the whole time
+    pub fn hold(&mut self, buttons: Buttons, ticks: u32) {
+        let getter = self.zone.terrain(&self.act);
+        for _ in 0..ticks {
+            self.game.step(&getter, buttons);
+        }
+    }
+
+    /// Holds the buttons for a single tick
+    pub fn press(&mut self, buttons: Buttons) {
+        self.hold(buttons, 1);
+    }
+
+    pub fn wait(&mut self, ticks: u32) {
+        self.hold(Buttons::NONE, ticks);
+    }
+
+    /// Plays out a script like `"hold right 120; press jump; wait 30"`, panicking if it's invalid
+    pub fn play(&mut self, script: &str) {
+        let script = script.parse::<Script>().unwrap();
+        for &(buttons, ticks) in script.0.iter() {
+            self.hold(buttons, ticks);
+        }
+    }
+
+    /// Plays a script, then keeps going with nothing held until the given tick
+    pub fn play_until(&mut self, script: &str, tick: u64) {
+        self.play(script);
+        assert!(self.tick() <= tick, "Script already ran until tick {}, past {}", self.tick(), tick);
+        self.wait((tick - self.tick()) as u32);
+    }
+}
+
+/// A list of buttons to hold down and how many ticks to hold each for.
+///
+/// Steps are separated by semicolons or newlines, and are one of
+/// `hold <buttons> <ticks>`, `press <buttons>` (held for a single tick) or `wait <ticks>`,
+/// where buttons are names like `left` joined with `+`, e.g. `hold right+down 10`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Script(pub Vec<(Buttons, u32)>);
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+
+        for step in s.split(&[';', '\n'][..]) {
+            let parts = step.split_whitespace().collect::<Vec<&str>>();
+            let step = match parts.as_slice() {
+                [] => continue,
+                ["hold", buttons, ticks] => (parse_buttons(buttons)?, parse_ticks(ticks)?),
+                ["press", buttons] => (parse_buttons(buttons)?, 1),
+                ["wait", ticks] => (Buttons::NONE, parse_ticks(ticks)?),
+                _ => return Err(format!("Invalid script step {:?}", step.trim())),
+            };
+            steps.push(step);
+        }
+
+        Ok(Script(steps))
+    }
+}
+
+fn parse_ticks(s: &str) -> Result<u32, String> {
+    s.parse::<u32>().map_err(|e| format!("Invalid tick count {:?}: {}", s, e))
+}
+
+fn parse_buttons(s: &str) -> Result<Buttons, String> {
+    s.split('+').try_fold(Buttons::NONE, |buttons, name| {
+        let button = match name {
+            "left" => Buttons::LEFT,
+            "right" => Buttons::RIGHT,
+            "up" => Buttons::UP,
+            "down" => Buttons::DOWN,
+            "jump" => Buttons::JUMP,
+            "none" => Buttons::NONE,
+            _ => return Err(format!("Invalid button {:?}", name)),
+        };
+        Ok(buttons | button)
+    })
+}
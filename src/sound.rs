@@ -0,0 +1,25 @@
+use sdl2::audio::{AudioFormat, AudioQueue, AudioSpecDesired, AudioSpecWAV};
+use sdl2::AudioSubsystem;
+
+/// Starts playing a 16-bit WAV file from the assets folder, e.g. `StageClear`.
+/// It keeps playing for as long as the returned queue is kept around.
+pub fn play_wav(audio: &AudioSubsystem, name: &str) -> Result<AudioQueue<i16>, String> {
+    let path = format!("./assets/{}.wav", name);
+    let wav = AudioSpecWAV::load_wav(&path).map_err(|e| format!("Could not load {}: {}", path, e))?;
+
+    if wav.format != AudioFormat::S16LSB {
+        return Err(format!("Expected {} to be 16-bit audio, got {:?}", path, wav.format));
+    }
+
+    let desired = AudioSpecDesired { freq: Some(wav.freq), channels: Some(wav.channels), samples: None };
+    let queue = audio.open_queue::<i16, _>(None, &desired)?;
+
+    let samples = wav
+        .buffer()
+        .chunks_exact(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect::<Vec<_>>();
+    queue.queue(&samples);
+    queue.resume();
+    Ok(queue)
+}
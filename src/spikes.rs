@@ -0,0 +1,125 @@
+use gamefox::physical::Vector2;
+use crate::behavior::{EntityBehavior, EntityContext, GameEvent};
+use crate::entity::Entity;
+use crate::flags::{choice_names, FlagSpec, FlagType};
+use crate::game::StateHasher;
+use crate::player::{Player, Side, SolidTop};
+use std::hash::Hash;
+
+pub const FLAGS: &[FlagSpec] = &[FlagSpec { name: "direction", kind: FlagType::Choice(&DIRECTION_NAMES), default: Some("u") }];
+
+/// Every way the direction flag can be written
+const DIRECTIONS: [(&str, SpikeDirection); 8] = [
+    ("u", SpikeDirection::Up),
+    ("up", SpikeDirection::Up),
+    ("d", SpikeDirection::Down),
+    ("down", SpikeDirection::Down),
+    ("l", SpikeDirection::Left),
+    ("left", SpikeDirection::Left),
+    ("r", SpikeDirection::Right),
+    ("right", SpikeDirection::Right),
+];
+const DIRECTION_NAMES: [&str; 8] = choice_names(&DIRECTIONS);
+
+/// Which way spikes point, from the direction flag of their entity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SpikeDirection {
+    #[default]
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl SpikeDirection {
+    /// How far the sprite, which points up, gets turned clockwise
+    fn rotation(self) -> f64 {
+        match self {
+            SpikeDirection::Up => 0.0,
+            SpikeDirection::Right => 90.0,
+            SpikeDirection::Down => 180.0,
+            SpikeDirection::Left => 270.0,
+        }
+    }
+
+    /// The side of the spikes they point out of
+    fn side(self) -> Side {
+        match self {
+            SpikeDirection::Up => Side::Top,
+            SpikeDirection::Down => Side::Bottom,
+            SpikeDirection::Left => Side::Left,
+            SpikeDirection::Right => Side::Right,
+        }
+    }
+}
+
+/// A solid block that hurts the player when they touch the side it points out of.
+/// Every other side is just a wall.
+#[derive(Debug, Default)]
+pub struct Spikes {
+    direction: SpikeDirection,
+}
+
+impl Spikes {
+    fn hurt(&self, entity: &Entity, ctx: &mut EntityContext) {
+        if !ctx.player.is_invulnerable() {
+            let source_x = entity.hitbox().map(|h| h.center().x() as f64).unwrap_or(entity.position().x);
+            ctx.events.push(GameEvent::HurtPlayer { source_x });
+        }
+    }
+}
+
+impl EntityBehavior for Spikes {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        self.direction = entity.flags().choice_of("direction", &DIRECTIONS);
+        entity.set_rotation(self.direction.rotation());
+    }
+
+    /// Standing on top of the spikes doesn't overlap them, so it gets checked for separately
+    fn update(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        let stood_on = self.top(entity).filter(|top| ctx.player.is_standing_on(top)).is_some();
+        if self.direction == SpikeDirection::Up && stood_on {
+            self.hurt(entity, ctx);
+        }
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        let hitbox = match entity.hitbox() {
+            Some(hitbox) => hitbox,
+            None => return,
+        };
+
+        let side = ctx.player.touched_side(hitbox);
+        match side {
+            Side::Left | Side::Right => {
+                ctx.player.push_out_of(hitbox.left() as f64, hitbox.right() as f64);
+            }
+            Side::Bottom => ctx.player.push_below(hitbox.bottom() as f64),
+            // Landing on top is taken care of by being solid
+            Side::Top => {}
+        }
+
+        if side == self.direction.side() {
+            self.hurt(entity, ctx);
+        }
+    }
+
+    fn is_solid(&self, _player: &Player) -> bool {
+        true
+    }
+
+    /// Spikes on their side have their hitbox turned with them
+    fn top(&self, entity: &Entity) -> Option<SolidTop> {
+        let hitbox = entity.hitbox()?;
+        Some(SolidTop {
+            left: hitbox.left() as f64,
+            right: hitbox.right() as f64,
+            y: hitbox.top() as f64,
+            velocity: Vector2 { x: 0.0, y: 0.0 },
+        })
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.direction.hash(hasher);
+    }
+}
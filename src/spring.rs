@@ -0,0 +1,156 @@
+use crate::behavior::{EntityBehavior, EntityContext};
+use crate::entity::Entity;
+use crate::flags::{choice_names, FlagSpec, FlagType};
+use crate::game::StateHasher;
+use std::hash::{Hash, Hasher};
+
+pub const FLAGS: &[FlagSpec] = &[
+    FlagSpec { name: "direction", kind: FlagType::Choice(&DIRECTION_NAMES), default: Some("u") },
+    FlagSpec { name: "color", kind: FlagType::Choice(&COLOR_NAMES), default: Some("yellow") },
+];
+
+/// Every way the direction flag can be written
+const DIRECTIONS: [(&str, SpringDirection); 16] = [
+    ("u", SpringDirection::Up),
+    ("up", SpringDirection::Up),
+    ("d", SpringDirection::Down),
+    ("down", SpringDirection::Down),
+    ("l", SpringDirection::Left),
+    ("left", SpringDirection::Left),
+    ("r", SpringDirection::Right),
+    ("right", SpringDirection::Right),
+    ("ul", SpringDirection::UpLeft),
+    ("upleft", SpringDirection::UpLeft),
+    ("ur", SpringDirection::UpRight),
+    ("upright", SpringDirection::UpRight),
+    ("dl", SpringDirection::DownLeft),
+    ("downleft", SpringDirection::DownLeft),
+    ("dr", SpringDirection::DownRight),
+    ("downright", SpringDirection::DownRight),
+];
+const DIRECTION_NAMES: [&str; 16] = choice_names(&DIRECTIONS);
+
+const COLORS: [(&str, SpringColor); 2] = [("yellow", SpringColor::Yellow), ("red", SpringColor::Red)];
+const COLOR_NAMES: [&str; 2] = choice_names(&COLORS);
+
+/// How long the stretched out sprite shows after bouncing something
+const BOUNCE_TIME: u32 = 10;
+
+/// Which way a spring launches the player, from the direction flag of its entity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SpringDirection {
+    #[default]
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+/// How strong a spring is, from the color flag of its entity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SpringColor {
+    #[default]
+    Yellow,
+    Red,
+}
+
+impl SpringDirection {
+    /// Which way along each axis the spring pushes, or 0 if it leaves that axis alone
+    pub fn push(self) -> (f64, f64) {
+        match self {
+            SpringDirection::Up => (0.0, -1.0),
+            SpringDirection::Down => (0.0, 1.0),
+            SpringDirection::Left => (-1.0, 0.0),
+            SpringDirection::Right => (1.0, 0.0),
+            SpringDirection::UpLeft => (-1.0, -1.0),
+            SpringDirection::UpRight => (1.0, -1.0),
+            SpringDirection::DownLeft => (-1.0, 1.0),
+            SpringDirection::DownRight => (1.0, 1.0),
+        }
+    }
+
+    /// How far the sprite, which faces up, gets turned clockwise
+    fn rotation(self) -> f64 {
+        match self {
+            SpringDirection::Up => 0.0,
+            SpringDirection::UpRight => 45.0,
+            SpringDirection::Right => 90.0,
+            SpringDirection::DownRight => 135.0,
+            SpringDirection::Down => 180.0,
+            SpringDirection::DownLeft => 225.0,
+            SpringDirection::Left => 270.0,
+            SpringDirection::UpLeft => 315.0,
+        }
+    }
+}
+
+/// Launches the player in its direction, at a speed that depends on its color,
+/// as soon as they touch it. Staying in contact doesn't launch them again.
+/// The first animation of the entity is the spring at rest, and the second is it bouncing.
+#[derive(Debug, Default)]
+pub struct Spring {
+    direction: SpringDirection,
+    color: SpringColor,
+    /// Ticks left before going back to rest
+    bounce_left: u32,
+    /// Whether the player is touching the spring this tick, and whether they were on the last one
+    touching: bool,
+    was_touching: bool,
+}
+
+impl EntityBehavior for Spring {
+    fn on_spawn(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        self.direction = entity.flags().choice_of("direction", &DIRECTIONS);
+        self.color = entity.flags().choice_of("color", &COLORS);
+        entity.set_rotation(self.direction.rotation());
+    }
+
+    fn update(&mut self, entity: &mut Entity, _ctx: &mut EntityContext) {
+        self.was_touching = self.touching;
+        self.touching = false;
+
+        if self.bounce_left > 0 {
+            self.bounce_left -= 1;
+            if self.bounce_left == 0 {
+                entity.set_animation_index(0);
+            }
+        }
+    }
+
+    fn on_player_touch(&mut self, entity: &mut Entity, ctx: &mut EntityContext) {
+        self.touching = true;
+        if self.was_touching {
+            return;
+        }
+
+        let physics = ctx.player.physics();
+        let speed = match self.color {
+            SpringColor::Yellow => physics.yellow_spring_speed,
+            SpringColor::Red => physics.red_spring_speed,
+        };
+
+        let (push_x, push_y) = self.direction.push();
+        let x = if push_x != 0.0 { Some(push_x * speed) } else { None };
+        let y = if push_y != 0.0 { Some(push_y * speed) } else { None };
+
+        // Only sideways springs stop the player from immediately turning around
+        let control_lock = if y.is_none() { physics.spring_control_lock } else { 0 };
+
+        ctx.player.launch(x, y, control_lock);
+
+        entity.set_animation_index(1);
+        self.bounce_left = BOUNCE_TIME;
+    }
+
+    fn hash_state(&self, hasher: &mut StateHasher) {
+        self.direction.hash(hasher);
+        self.color.hash(hasher);
+        hasher.write_u32(self.bounce_left);
+        self.touching.hash(hasher);
+        self.was_touching.hash(hasher);
+    }
+}
@@ -0,0 +1,126 @@
+use std::time::{Duration, Instant};
+
+/// How many times per second the game simulation runs
+pub const TICKS_PER_SECOND: u32 = 60;
+
+/// Converts a length of time into the closest number of ticks,
+/// never rounding anything that isn't zero all the way down to nothing
+pub fn duration_to_ticks(duration: Duration) -> u32 {
+    if duration == Duration::new(0, 0) {
+        return 0;
+    }
+
+    let ticks = (duration.as_secs_f64() * TICKS_PER_SECOND as f64).round() as u32;
+    ticks.max(1)
+}
+
+/// The most simulation ticks that get run to catch up in a single frame.
+/// Anything beyond that is dropped, so a long stall slows the game down
+/// instead of making it spend even longer catching up.
+const MAX_TICKS_PER_FRAME: u32 = 5;
+
+/// Splits real time up into equally long simulation ticks
+pub struct FixedTimestep {
+    tick: Duration,
+    accumulator: Duration,
+    last_frame: Instant,
+}
+
+impl FixedTimestep {
+    pub fn new(ticks_per_second: u32) -> FixedTimestep {
+        FixedTimestep {
+            tick: Duration::from_secs(1) / ticks_per_second,
+            accumulator: Duration::new(0, 0),
+            last_frame: Instant::now(),
+        }
+    }
+
+    pub fn tick_length(&self) -> Duration {
+        self.tick
+    }
+
+    /// Adds on the time since the last call, and returns how many ticks should be simulated now
+    pub fn advance(&mut self) -> u32 {
+        let now = Instant::now();
+        self.accumulator += now - self.last_frame;
+        self.last_frame = now;
+
+        let max_backlog = self.tick * MAX_TICKS_PER_FRAME;
+        if self.accumulator > max_backlog {
+            self.accumulator = max_backlog;
+        }
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick {
+            self.accumulator -= self.tick;
+            ticks += 1;
+        }
+
+        ticks
+    }
+
+    /// How far along the next tick currently is, from 0 to 1,
+    /// for blending between the last two simulated states
+    pub fn alpha(&self) -> f64 {
+        self.accumulator.as_secs_f64() / self.tick.as_secs_f64()
+    }
+
+    pub fn time_until_next_tick(&self) -> Duration {
+        self.tick - self.accumulator
+    }
+}
+
+/// Keeps track of how long updating and rendering take compared to the time available each tick
+pub struct FrameStats {
+    budget: Duration,
+    frames: u32,
+    ticks: u32,
+    update_time: Duration,
+    render_time: Duration,
+    worst_frame: Duration,
+    last_report: Instant,
+}
+
+impl FrameStats {
+    pub fn new(budget: Duration) -> FrameStats {
+        FrameStats {
+            budget,
+            frames: 0,
+            ticks: 0,
+            update_time: Duration::new(0, 0),
+            render_time: Duration::new(0, 0),
+            worst_frame: Duration::new(0, 0),
+            last_report: Instant::now(),
+        }
+    }
+
+    pub fn record_frame(&mut self, ticks: u32, update_time: Duration, render_time: Duration) {
+        self.frames += 1;
+        self.ticks += ticks;
+        self.update_time += update_time;
+        self.render_time += render_time;
+        self.worst_frame = self.worst_frame.max(update_time + render_time);
+    }
+
+    /// Returns a summary of the last second or so, and starts over
+    pub fn report(&mut self) -> Option<String> {
+        if self.last_report.elapsed() < Duration::from_secs(1) || self.frames == 0 {
+            return None;
+        }
+
+        let millis = |d: Duration| d.as_secs_f64() * 1000.0;
+        let ticks = self.ticks.max(1);
+        let report = format!(
+            "{} frames, {} ticks | update {:.2} ms/tick, render {:.2} ms/frame, worst frame {:.2} ms (budget {:.2} ms)",
+            self.frames,
+            self.ticks,
+            millis(self.update_time) / ticks as f64,
+            millis(self.render_time) / self.frames as f64,
+            millis(self.worst_frame),
+            millis(self.budget),
+        );
+
+        *self = FrameStats::new(self.budget);
+        Some(report)
+    }
+}
@@ -0,0 +1,89 @@
+use gamefox::asset_mgr::GraphicsHolder;
+use gamefox::camera::Camera;
+use crate::act::ActFile;
+use crate::block::{self, Block, CollisionTile, TerrainGetter, Tileset};
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{Canvas, RenderTarget, TextureCreator};
+use std::path::Path;
+
+/// The terrain shared by every act of a zone, loaded from plain files.
+///
+/// Nothing here needs SDL, so collision works without a window;
+/// the block textures only get built by calling `add_graphics`.
+pub struct Zone {
+    pub name: String,
+    pub blocks: Vec<Block>,
+    pub collision_tiles: Vec<CollisionTile>,
+    pub tileset: Tileset,
+}
+
+impl Zone {
+    /// Loads the blocks in `./assets/<name>/` and the collision tiles they use
+    pub fn load(name: &str) -> Result<Zone, String> {
+        let assets = Path::new("./assets/");
+
+        let blocks = block::load_blocks(&assets.join(name), "Block")
+            .map_err(|e| format!("Could not load blocks of {}: {}", name, e))?;
+        if blocks.is_empty() {
+            return Err(format!("No blocks found for {}", name));
+        }
+
+        let collision_tiles = block::load_collision_tiles(&assets.join("CollisionTiles.txt"))?;
+
+        Ok(Zone {
+            name: name.to_string(),
+            blocks,
+            collision_tiles,
+            tileset: Tileset { tiles_per_row: 20, image: name.to_string() },
+        })
+    }
+
+    /// The terrain of an act laid out with this zone's blocks
+    pub fn terrain<'a>(&'a self, act: &'a ActFile) -> TerrainGetter<'a> {
+        TerrainGetter::new(act.width, &act.tiles, &self.blocks, &self.collision_tiles)
+    }
+
+    /// Draws every block into a texture named `BLOCK<index>`,
+    /// which needs the tileset image to already be in the `GraphicsHolder`
+    pub fn add_graphics<T>(&self, creator: &'static TextureCreator<T>) {
+        Block::add_graphics_multi(
+            self.blocks.iter().enumerate().map(|(i, block)| (format!("BLOCK{}", i), block)),
+            &self.tileset,
+            creator,
+        );
+    }
+
+    /// Draws the blocks of an act, along with lines marking where each one starts
+    pub fn render_act<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera, act: &ActFile) -> Result<(), String> {
+        let holder = GraphicsHolder::get();
+
+        for r in 0..act.tiles.len() / act.width {
+            for c in 0..act.width {
+                let x = (c * 128) as f64 - camera.position.x;
+                let y = (r * 128) as f64 - camera.position.y;
+
+                canvas.set_draw_color(Color::RGB(255, 255, 255));
+                canvas.draw_line(Point::new(x as i32, y as i32), Point::new(x as i32, y as i32 + 600))?;
+                canvas.draw_line(Point::new(x as i32, y as i32), Point::new(x as i32 + 600, y as i32))?;
+
+                if let Some((block_idx, block_flags)) = act.tiles[r * act.width + c] {
+                    let name = format!("BLOCK{}", block_idx);
+                    let (_, texture) = holder.get_pair(&name).ok_or_else(|| format!("Missing image {}", name))?;
+                    canvas.copy_ex(
+                        texture,
+                        None,
+                        Rect::new(x as i32, y as i32, 128, 128),
+                        0.0,
+                        None,
+                        // TODO: Support more flags
+                        block_flags != 0,
+                        false,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,842 @@
+use gamefox::physical::Vector2;
+use project_tails::entity::{entity_kind, Entity};
+use project_tails::player::{find_ground_height, PlayerState};
+use project_tails::scenario::{Scenario, Script};
+use project_tails::{ActFile, Buttons, PhysicsProfile};
+
+const ACT_1: &str = "./assets/Act1Data.txt";
+
+/// Act 1 with classic physics, after the player has fallen from the start and landed
+fn landed() -> Scenario {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap();
+    scenario.wait(120);
+    scenario
+}
+
+/// An entity of the given kind with the bottom of its hitbox resting on `ground`
+fn place(kind: &str, x: f64, ground: f64, flags: &[&str]) -> Entity {
+    let definition = entity_kind(kind).unwrap();
+    let y = ground - definition.hitbox_offset.1 as f64 - definition.hitbox_size.1 as f64;
+    Entity::new(Vector2 { x, y }, kind.to_string(), flags.iter().map(|flag| flag.to_string()).collect()).unwrap()
+}
+
+#[test]
+fn script_parsing() {
+    let script = "hold right+down 10; press jump\nwait 5".parse::<Script>().unwrap();
+    assert_eq!(script.0, vec![(Buttons::RIGHT | Buttons::DOWN, 10), (Buttons::JUMP, 1), (Buttons::NONE, 5)]);
+
+    assert!("hold right".parse::<Script>().is_err());
+    assert!("press sideways".parse::<Script>().is_err());
+}
+
+#[test]
+fn falls_onto_ground_at_start() {
+    let scenario = landed();
+    let player = scenario.player();
+
+    assert_eq!(player.state(), PlayerState::Ground);
+    assert_eq!(player.velocity().y, 0.0);
+
+    let position = scenario.position();
+    let (ground, _angle) = find_ground_height(position, 5.0, 10.0, 0, &scenario.getter()).unwrap();
+    assert_eq!(position.y, ground as f64);
+}
+
+#[test]
+fn dropped_player_lands_on_ground_below() {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(1000.0, 300.0);
+    scenario.wait(120);
+
+    let position = scenario.position();
+    // The player starts out drifting slightly to the right
+    assert!(position.x >= 1000.0 && position.x < 1005.0);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+
+    let (ground, _angle) = find_ground_height(position, 5.0, 10.0, 0, &scenario.getter()).unwrap();
+    assert_eq!(position.y, ground as f64);
+}
+
+#[test]
+fn holding_right_accelerates_along_ground() {
+    let mut scenario = landed();
+    let start = scenario.position();
+
+    scenario.play("hold right 60");
+    let player = scenario.player();
+
+    assert_eq!(player.state(), PlayerState::Ground);
+    assert_eq!(player.velocity().x, 60.0 * 0.046875);
+    assert!(scenario.position().x > start.x + 60.0);
+    assert_eq!(scenario.position().y, start.y);
+}
+
+#[test]
+fn letting_go_slows_back_down() {
+    let mut scenario = landed();
+    scenario.play("hold right 30; wait 60");
+
+    assert_eq!(scenario.player().velocity().x, 0.0);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+}
+
+#[test]
+fn jumping_lands_back_on_ground() {
+    let mut scenario = landed();
+    let start = scenario.position();
+
+    scenario.press(Buttons::JUMP);
+    assert_eq!(scenario.player().state(), PlayerState::Jumping);
+    assert_eq!(scenario.player().velocity().y, -6.5);
+
+    scenario.wait(15);
+    assert!(scenario.position().y < start.y - 50.0);
+
+    scenario.play_until("", scenario.tick() + 60);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert_eq!(scenario.position().x, start.x);
+    assert_eq!(scenario.position().y, start.y);
+}
+
+#[test]
+fn pressing_jump_in_the_air_starts_flying() {
+    let mut scenario = landed();
+    scenario.play("press jump; wait 10; press jump; hold jump 20");
+
+    let fly_time = PhysicsProfile::load("classic").unwrap().fly_time;
+    assert_eq!(scenario.player().state(), PlayerState::Flying(fly_time - 20));
+}
+
+#[test]
+fn slow_jump_leaves_the_ground_and_then_flies() {
+    // Floaty jumps start slower than the ground snap distance, so they'd get snapped straight back down
+    let mut scenario = Scenario::load(ACT_1, "floaty").unwrap();
+    scenario.wait(600);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    let start = scenario.position();
+
+    scenario.press(Buttons::JUMP);
+    scenario.wait(1);
+    assert_eq!(scenario.player().state(), PlayerState::Jumping);
+    assert!(scenario.position().y < start.y);
+
+    scenario.play("wait 10; press jump");
+    let fly_time = PhysicsProfile::load("floaty").unwrap().fly_time;
+    assert_eq!(scenario.player().state(), PlayerState::Flying(fly_time));
+}
+
+#[test]
+fn spindash_launches_into_a_roll() {
+    let mut scenario = landed();
+    let start = scenario.position();
+
+    scenario.play("hold down 5");
+    assert_eq!(scenario.player().state(), PlayerState::Crouching);
+
+    scenario.play("press down+jump; hold down 5; press down+jump; hold down 5");
+    assert_eq!(scenario.player().state(), PlayerState::Spindash);
+    assert_eq!(scenario.position().x, start.x);
+
+    scenario.play("wait 1");
+    assert_eq!(scenario.player().state(), PlayerState::Rolling);
+    assert!(scenario.player().velocity().x > 8.0);
+}
+
+#[test]
+fn getting_hurt_scatters_rings() {
+    let mut scenario = landed();
+    for _ in 0..5 {
+        scenario.game.player.collect_ring();
+    }
+
+    let x = scenario.position().x;
+    scenario.game.hurt_player(x + 10.0);
+    assert_eq!(scenario.player().rings(), 0);
+    assert_eq!(scenario.player().state(), PlayerState::Hurt);
+    assert_eq!(scenario.game.lost_rings.len(), 5);
+    assert!(scenario.player().velocity().x < 0.0);
+
+    scenario.wait(60);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert!(scenario.player().is_invulnerable());
+
+    // Without any rings left, the next hit is fatal
+    scenario.wait(120);
+    let lives = scenario.player().lives();
+    scenario.game.hurt_player(x);
+    assert!(matches!(scenario.player().state(), PlayerState::Dead(_)));
+    assert_eq!(scenario.player().lives(), lives - 1);
+}
+
+#[test]
+fn losing_the_last_life_is_game_over() {
+    let mut scenario = landed();
+    let death_time = scenario.player().physics().death_time;
+    for lives in (0..3).rev() {
+        scenario.game.player.die();
+        assert_eq!(scenario.player().lives(), lives);
+        scenario.wait(death_time + 1);
+    }
+    assert_eq!(scenario.player().state(), PlayerState::GameOver);
+
+    // There's no coming back from it
+    scenario.wait(600);
+    assert_eq!(scenario.player().state(), PlayerState::GameOver);
+    assert_eq!(scenario.player().lives(), 0);
+}
+
+#[test]
+fn enemy_projectile_hurts_player() {
+    let mut scenario = landed();
+    for _ in 0..3 {
+        scenario.game.player.collect_ring();
+    }
+
+    let position = scenario.position();
+    let projectile = place("BEEPROJECTILE", position.x - 4.0, position.y - 5.0, &[]);
+    scenario.game.entities.push(projectile);
+
+    scenario.wait(1);
+    assert_eq!(scenario.player().state(), PlayerState::Hurt);
+    assert_eq!(scenario.game.lost_rings.len(), 3);
+}
+
+#[test]
+fn touching_a_ring_collects_it() {
+    let mut scenario = landed();
+    let entities = scenario.game.entities.len();
+
+    let position = scenario.position();
+    let ring = place("RING", position.x - 8.0, position.y, &[]);
+    scenario.game.entities.push(ring);
+
+    scenario.wait(1);
+    assert_eq!(scenario.player().rings(), 1);
+
+    // It only counts once, then disappears after sparkling
+    scenario.wait(10);
+    assert_eq!(scenario.player().rings(), 1);
+    assert_eq!(scenario.game.entities.len(), entities + 1);
+    scenario.wait(20);
+    assert_eq!(scenario.game.entities.len(), entities);
+}
+
+#[test]
+fn running_through_the_first_rings() {
+    let mut scenario = landed();
+    scenario.play("hold right 300");
+    assert_eq!(scenario.player().rings(), 4);
+}
+
+#[test]
+fn hundred_rings_give_an_extra_life() {
+    let mut scenario = landed();
+    let lives = scenario.player().lives();
+
+    for _ in 0..99 {
+        scenario.game.player.collect_ring();
+    }
+    assert_eq!(scenario.player().lives(), lives);
+
+    scenario.game.player.collect_ring();
+    assert_eq!(scenario.player().lives(), lives + 1);
+
+    // Dropping below 100 and getting back up again doesn't count twice
+    let x = scenario.position().x;
+    scenario.game.hurt_player(x);
+    for _ in 0..100 {
+        scenario.game.player.collect_ring();
+    }
+    assert_eq!(scenario.player().lives(), lives + 1);
+}
+
+fn spring(scenario: &Scenario, flags: &[&str]) -> Entity {
+    let position = scenario.position();
+    place("SPRINGYELLOW", position.x - 16.0, position.y, flags)
+}
+
+#[test]
+fn up_spring_launches_into_the_air() {
+    let mut scenario = landed();
+    let spring = spring(&scenario, &["u"]);
+    scenario.game.entities.push(spring);
+
+    scenario.wait(1);
+    assert_eq!(scenario.player().state(), PlayerState::Airborne);
+    assert_eq!(scenario.player().velocity().y, -10.0);
+    assert_eq!(scenario.player().velocity().x, 0.0);
+
+    scenario.wait(10);
+    assert!(scenario.position().y < 500.0);
+}
+
+#[test]
+fn red_spring_launches_faster() {
+    let mut scenario = landed();
+    let spring = spring(&scenario, &["u", "red"]);
+    scenario.game.entities.push(spring);
+
+    scenario.wait(1);
+    assert_eq!(scenario.player().velocity().y, -16.0);
+}
+
+#[test]
+fn spring_only_launches_when_first_touched() {
+    // Floaty springs are slow enough that the player is still touching it on the next tick
+    let mut scenario = Scenario::load(ACT_1, "floaty").unwrap();
+    scenario.wait(600);
+    let spring = spring(&scenario, &["u"]);
+    scenario.game.entities.push(spring);
+
+    scenario.wait(1);
+    assert_eq!(scenario.player().velocity().y, -1.8);
+    scenario.wait(1);
+    assert!(scenario.player().hitbox().has_intersection(scenario.game.entities.last().unwrap().hitbox().unwrap()));
+    assert!(scenario.player().velocity().y > -1.8);
+}
+
+#[test]
+fn sideways_spring_locks_controls() {
+    let mut scenario = landed();
+    let spring = spring(&scenario, &["l"]);
+    scenario.game.entities.push(spring);
+
+    scenario.wait(1);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert_eq!(scenario.player().velocity().x, -10.0);
+
+    // Holding right does nothing until the lock wears off
+    scenario.play("hold right 10");
+    assert!(scenario.player().velocity().x < -9.0);
+    scenario.play("hold right 30");
+    assert!(scenario.player().velocity().x > -9.0);
+}
+
+#[test]
+fn legacy_spring_name_is_accepted() {
+    let spring = "2391 340 SPRING_YELLOW u".parse::<Entity>().unwrap();
+    assert_eq!(spring.kind().unwrap().name, "SPRINGYELLOW");
+    assert_eq!(spring.to_string(), "2391 340 SPRINGYELLOW u ");
+}
+
+#[test]
+fn same_script_plays_out_the_same() {
+    let script = "hold right 90; press jump; hold right 40; hold down 20; wait 100";
+
+    let mut first = Scenario::load(ACT_1, "floaty").unwrap();
+    let mut second = Scenario::load(ACT_1, "floaty").unwrap();
+    first.play(script);
+    second.play(script);
+
+    assert_eq!(first.tick(), 251);
+    assert_eq!(first.game.state_hash(), second.game.state_hash());
+}
+
+/// Act 1 with the player standing left of the loop, which path 0 runs under the near side of
+fn before_loop() -> Scenario {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(800.0, 650.0);
+    scenario.wait(60);
+    scenario
+}
+
+/// Holds right until the player gets past the given x position
+fn run_right_past(scenario: &mut Scenario, x: f64) {
+    while scenario.position().x < x {
+        scenario.hold(Buttons::RIGHT, 1);
+    }
+}
+
+#[test]
+fn running_into_the_loop_climbs_its_far_side() {
+    let mut scenario = before_loop();
+    run_right_past(&mut scenario, 1120.0);
+
+    assert_eq!(scenario.player().layer(), 0);
+    assert!(scenario.position().y < 660.0);
+}
+
+#[test]
+fn inside_path_switch_runs_under_the_loop() {
+    let mut scenario = before_loop();
+    scenario.game.entities.push(place("PATHSWITCH", 1000.0, 706.0, &["i"]));
+
+    run_right_past(&mut scenario, 1020.0);
+    assert_eq!(scenario.player().layer(), 1);
+
+    // The far side of the loop isn't on path 1
+    run_right_past(&mut scenario, 1120.0);
+    assert!(scenario.position().y > 700.0);
+
+    // The act's own side switch at the loop's exit
+    run_right_past(&mut scenario, 1200.0);
+    assert_eq!(scenario.player().layer(), 0);
+}
+
+#[test]
+fn side_path_switch_sends_the_player_back_up_the_loop() {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(1250.0, 700.0);
+    scenario.wait(60);
+
+    while scenario.position().x > 1140.0 {
+        scenario.hold(Buttons::LEFT, 1);
+    }
+    assert_eq!(scenario.player().layer(), 1);
+
+    while scenario.position().x > 970.0 {
+        scenario.hold(Buttons::LEFT, 1);
+    }
+    let position = scenario.position();
+    let getter = scenario.getter();
+    let (ground, _angle) = find_ground_height(position, 5.0, 10.0, 1, &getter).unwrap();
+    let (under, _angle) = find_ground_height(position, 5.0, 10.0, 0, &getter).unwrap();
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert_eq!(position.y, ground as f64);
+    assert!(position.y < under as f64);
+}
+
+#[test]
+fn under_path_switch_puts_the_player_back_on_path_0() {
+    let mut scenario = before_loop();
+    scenario.game.player.set_layer(1);
+
+    // The act's own under switch at the loop's entrance
+    run_right_past(&mut scenario, 900.0);
+    assert_eq!(scenario.player().layer(), 0);
+}
+
+/// Drops the player onto a platform floating in the air, well above the ground
+fn on_platform(flags: &[&str]) -> Scenario {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(1000.0, 480.0);
+    scenario.game.entities.push(place("PLATFORM", 968.0, 532.0, flags));
+    scenario.wait(60);
+    scenario
+}
+
+/// The platform added by `on_platform`, which comes after the ones already in the act
+fn platform(scenario: &Scenario) -> &Entity {
+    scenario.game.entities.iter().rev().find(|e| e.is_solid(scenario.player())).unwrap()
+}
+
+fn platform_top(scenario: &Scenario) -> f64 {
+    platform(scenario).top().unwrap().y
+}
+
+#[test]
+fn landing_on_a_still_platform() {
+    let scenario = on_platform(&[]);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert_eq!(scenario.position().y, 508.0);
+    assert_eq!(platform_top(&scenario), 508.0);
+}
+
+#[test]
+fn moving_platform_carries_the_player() {
+    let mut scenario = on_platform(&["v", "32", "120"]);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+
+    for _ in 0..120 {
+        scenario.wait(1);
+        assert_eq!(scenario.player().state(), PlayerState::Ground);
+        assert!((scenario.position().y - platform_top(&scenario)).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn jumping_off_a_platform_keeps_its_velocity() {
+    let mut scenario = on_platform(&["h", "32", "120"]);
+    let start = scenario.position();
+    scenario.press(Buttons::JUMP);
+
+    let platform = platform(&scenario);
+    assert_eq!(scenario.player().state(), PlayerState::Jumping);
+    assert!(platform.velocity().x != 0.0);
+    assert!((scenario.player().velocity().x - platform.velocity().x).abs() < 1e-9);
+    assert!(scenario.position().x != start.x);
+}
+
+#[test]
+fn falling_platform_drops_after_being_stood_on() {
+    let mut scenario = on_platform(&["f"]);
+    // The player lands a few ticks in, and it holds on for a while before dropping
+    assert!(platform_top(&scenario) > 508.0);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert!((scenario.position().y - platform_top(&scenario)).abs() < 1e-9);
+
+    // It falls right through the terrain, leaving the player behind on the ground,
+    // and is gone once it drops out of the act
+    let solids = scenario.game.entities.iter().filter(|e| e.is_solid(scenario.player())).count();
+    scenario.wait(120);
+    assert_eq!(scenario.position().y, 705.0);
+    assert_eq!(scenario.game.entities.iter().filter(|e| e.is_solid(scenario.player())).count(), solids - 1);
+}
+
+/// Classic physics on a flat stretch of ground, out of reach of the act's own badniks
+fn clear_ground() -> Scenario {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(800.0, 600.0);
+    scenario.wait(60);
+    scenario
+}
+
+fn count_kind(scenario: &Scenario, kind: &str) -> usize {
+    scenario.game.entities.iter().filter(|e| e.kind().filter(|k| k.name == kind).is_some()).count()
+}
+
+#[test]
+fn walking_crab_hurts_standing_player() {
+    let mut scenario = clear_ground();
+    scenario.game.player.collect_ring();
+    let position = scenario.position();
+    scenario.game.entities.push(place("CRABBADNIK", position.x + 40.0, position.y, &[]));
+
+    scenario.wait(100);
+    assert_eq!(scenario.player().rings(), 0);
+    assert_eq!(scenario.player().score(), 0);
+    assert_eq!(count_kind(&scenario, "CRABBADNIK"), 1);
+}
+
+#[test]
+fn rolling_into_a_crab_destroys_it() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.entities.push(place("CRABBADNIK", position.x + 40.0, position.y, &[]));
+
+    scenario.play("hold down 5; press down+jump; hold down 5; wait 10");
+    assert_eq!(scenario.player().score(), 100);
+    assert_eq!(scenario.player().state(), PlayerState::Rolling);
+
+    // It sticks around just long enough to explode
+    assert_eq!(count_kind(&scenario, "CRABBADNIK"), 1);
+    scenario.wait(60);
+    assert_eq!(count_kind(&scenario, "CRABBADNIK"), 0);
+}
+
+#[test]
+fn bee_fires_down_at_the_player() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    // Hovering well above the player's head
+    scenario.game.entities.push(place("BEEBADNIK", position.x - 21.0, position.y - 64.0, &[]));
+
+    scenario.wait(20);
+    assert_eq!(count_kind(&scenario, "BEEPROJECTILE"), 0);
+    scenario.wait(10);
+    assert_eq!(count_kind(&scenario, "BEEPROJECTILE"), 1);
+
+    let projectile = scenario.game.entities.iter().find(|e| e.kind().filter(|k| k.name == "BEEPROJECTILE").is_some()).unwrap();
+    assert!(projectile.velocity().x < 0.0 && projectile.velocity().y > 0.0);
+}
+
+#[test]
+fn walking_into_a_monitor_is_blocked() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.entities.push(place("RINGMONITOR", position.x + 20.0, position.y, &["rings"]));
+    let monitors = count_kind(&scenario, "RINGMONITOR");
+
+    scenario.play("hold right 60");
+    assert!(scenario.position().x <= position.x + 15.0);
+    assert_eq!(count_kind(&scenario, "RINGMONITOR"), monitors);
+}
+
+#[test]
+fn rolling_into_a_monitor_gives_its_item() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.entities.push(place("RINGMONITOR", position.x + 40.0, position.y, &["shield"]));
+    let monitors = count_kind(&scenario, "RINGMONITOR");
+
+    scenario.play("hold down 5; press down+jump; hold down 5; wait 10");
+    assert_eq!(count_kind(&scenario, "MONITORICON"), 1);
+    assert!(!scenario.player().has_shield());
+
+    // The item only gets given once the icon has finished rising
+    scenario.wait(40);
+    assert!(scenario.player().has_shield());
+    assert_eq!(count_kind(&scenario, "RINGMONITOR"), monitors - 1);
+
+    // The shield takes the next hit instead of the rings
+    scenario.game.player.collect_ring();
+    scenario.game.hurt_player(0.0);
+    assert!(!scenario.player().has_shield());
+    assert_eq!(scenario.player().rings(), 1);
+}
+
+#[test]
+fn monitor_icons_have_an_animation_for_every_item() {
+    let icon = entity_kind("MONITORICON").unwrap();
+    for image in ["Ring", "Lives", "IconShoes", "IconInvincibility", "IconShield"] {
+        assert!(icon.animation(image).is_some(), "No {} icon", image);
+    }
+}
+
+#[test]
+fn jumping_onto_a_monitor_breaks_it() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.entities.push(place("RINGMONITOR", position.x - 14.0, position.y, &["rings"]));
+    let monitors = count_kind(&scenario, "RINGMONITOR");
+    scenario = scenario.start_at(position.x, position.y - 60.0);
+
+    // Falling onto it without being curled up just lands on top
+    scenario.wait(60);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert_eq!(scenario.position().y, position.y - 32.0);
+
+    // Coming back down curled up breaks it, bouncing back up
+    scenario.press(Buttons::JUMP);
+    scenario.wait(70);
+    assert!(scenario.player().velocity().y < 0.0);
+
+    scenario.wait(50);
+    assert_eq!(count_kind(&scenario, "RINGMONITOR"), monitors - 1);
+    assert_eq!(scenario.player().rings(), 10);
+}
+
+#[test]
+fn landing_on_the_edge_of_a_monitor_stays_on_top() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.entities.push(place("RINGMONITOR", position.x - 2.0, position.y, &["rings"]));
+    scenario = scenario.start_at(position.x, position.y - 60.0);
+
+    // Nothing pushes the player sideways on the way down, even with the monitor's edge right under them
+    for _ in 0..60 {
+        scenario.wait(1);
+        assert_eq!(scenario.position().x, position.x);
+    }
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert_eq!(scenario.position().y, position.y - 32.0);
+}
+
+#[test]
+fn passing_the_goal_post_clears_the_act() {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(2700.0, 300.0);
+    scenario.wait(60);
+    for _ in 0..3 {
+        scenario.game.player.collect_ring();
+    }
+
+    scenario.hold(Buttons::RIGHT, 120);
+    let clear = scenario.game.act_clear().cloned().expect("passed the goal post");
+    assert_eq!(clear.act_time, scenario.game.act_time());
+    assert_eq!(clear.ring_bonus, 300);
+    assert!(clear.time_bonus > 0);
+
+    // The player isn't in control anymore, so holding right doesn't do anything
+    scenario.hold(Buttons::RIGHT, 120);
+    assert_eq!(scenario.player().velocity().x, 0.0);
+    assert_eq!(scenario.game.camera_focus(1.0), clear.focus);
+    assert_eq!(scenario.game.act_time(), clear.act_time);
+
+    let score = scenario.player().score();
+    scenario.wait(2000);
+    let finished = scenario.game.act_clear().unwrap();
+    assert!(finished.is_finished());
+    assert_eq!((finished.time_bonus, finished.ring_bonus), (0, 0));
+    assert_eq!(scenario.player().score(), score + clear.time_bonus + clear.ring_bonus);
+}
+
+#[test]
+fn next_act_comes_from_the_act_number() {
+    let act = ActFile::load(ACT_1).unwrap();
+    assert_eq!(act.number(), Some(1));
+    assert_eq!(act.next_act_path(ACT_1).as_deref(), Some("./assets/Act2Data.txt"));
+}
+
+#[test]
+fn acts_name_their_zone() {
+    let act = ActFile::load(ACT_1).unwrap();
+    assert_eq!(act.zone, "EmeraldHillZone");
+    assert_eq!(format!("{}", act).parse::<ActFile>().unwrap(), act);
+}
+
+/// A 12 log bridge above the flat ground, with the player standing on its 7th log
+fn on_bridge() -> Scenario {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(816.0, 600.0);
+    scenario.game.entities.push(place("BRIDGE", 720.0, 676.0, &["12"]));
+    scenario.wait(60);
+    scenario
+}
+
+fn bridge_top(scenario: &Scenario) -> f64 {
+    let bridge = scenario.game.entities.iter().rev().find(|e| e.kind().filter(|k| k.name == "BRIDGE").is_some()).unwrap();
+    bridge.top().unwrap().y
+}
+
+#[test]
+fn bridge_sags_deepest_under_the_player() {
+    let mut scenario = on_bridge();
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    // Six logs from the right end, at two pixels each
+    assert_eq!(scenario.position().y, 672.0);
+    assert_eq!(bridge_top(&scenario), 672.0);
+
+    // Closer to the end, it doesn't sag as much
+    scenario.play("hold left 40; wait 60");
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert!(scenario.position().x < 760.0);
+    assert!(scenario.position().y > 660.0 && scenario.position().y < 672.0);
+    assert_eq!(scenario.position().y, bridge_top(&scenario));
+}
+
+#[test]
+fn bridge_straightens_out_after_the_player_leaves() {
+    let mut scenario = on_bridge();
+    scenario.press(Buttons::JUMP);
+    scenario.wait(10);
+    let sag = bridge_top(&scenario);
+    assert!(sag > 660.0 && sag < 672.0);
+
+    scenario.wait(10);
+    assert_eq!(bridge_top(&scenario), 660.0);
+}
+
+#[test]
+fn landing_on_spikes_hurts() {
+    let mut scenario = Scenario::load(ACT_1, "classic").unwrap().start_at(800.0, 600.0);
+    scenario.game.player.collect_ring();
+    scenario.game.entities.push(place("SPIKES", 785.0, 714.0, &["up"]));
+
+    scenario.wait(40);
+    assert_eq!(scenario.player().state(), PlayerState::Hurt);
+    assert_eq!(scenario.game.lost_rings.len(), 1);
+}
+
+#[test]
+fn sides_of_spikes_are_walls() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.entities.push(place("SPIKES", position.x + 20.0, position.y, &["up"]));
+
+    scenario.play("hold right 60");
+    assert!(scenario.position().x <= position.x + 20.0);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+}
+
+#[test]
+fn spikes_pointing_at_the_player_hurt_unless_invulnerable() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.player.collect_ring();
+    scenario.game.entities.push(place("SPIKES", position.x + 20.0, position.y, &["left"]));
+
+    scenario.game.player.give_invincibility(120);
+    scenario.play("hold right 60");
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    assert!(scenario.position().x <= position.x + 20.0);
+
+    scenario.play("wait 60; hold right 30");
+    assert_eq!(scenario.player().state(), PlayerState::Hurt);
+    assert!(scenario.player().velocity().x < 0.0);
+}
+
+#[test]
+fn spikes_dont_hurt_while_blinking_after_a_hit() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.player.collect_ring();
+    scenario.game.entities.push(place("SPIKES", position.x + 120.0, position.y, &["left"]));
+
+    // Knocked back towards the spikes without reaching them, then walking into them while still blinking
+    scenario.game.hurt_player(position.x - 10.0);
+    scenario.wait(60);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+    scenario.play("hold right 40");
+    assert!(scenario.player().is_invulnerable());
+    assert!(scenario.position().x > position.x + 110.0 && scenario.position().x <= position.x + 115.0);
+    assert_eq!(scenario.player().state(), PlayerState::Ground);
+}
+
+#[test]
+fn entities_out_of_range_are_dormant() {
+    let mut scenario = clear_ground();
+    let ring = Entity::new(Vector2 { x: 2000.0, y: 600.0 }, "RING".to_string(), Vec::new()).unwrap();
+    scenario.game.entities.push(ring);
+    scenario.wait(1);
+    assert!(!scenario.game.entities.last().unwrap().is_active());
+
+    scenario.game.player.set_position(Vector2 { x: 1800.0, y: 600.0 });
+    scenario.wait(1);
+    assert!(scenario.game.entities.last().unwrap().is_active());
+}
+
+#[test]
+fn badniks_go_back_to_their_spawn_point_out_of_range() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    let crab = place("CRABBADNIK", position.x + 100.0, position.y, &[]);
+    let spawn = crab.position();
+    scenario.game.entities.push(crab);
+    scenario.wait(60);
+    let crab = scenario.game.entities.last().unwrap();
+    assert!(crab.is_active());
+    assert_ne!(crab.position(), spawn);
+
+    scenario.game.player.set_position(Vector2 { x: 2700.0, y: 300.0 });
+    scenario.wait(1);
+    let crab = scenario.game.entities.last().unwrap();
+    assert!(!crab.is_active());
+    assert_eq!(crab.position(), spawn);
+}
+
+#[test]
+fn destroyed_entities_stay_destroyed() {
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    let rings = count_kind(&scenario, "RING");
+    scenario.game.entities.push(place("RING", position.x - 8.0, position.y - 8.0, &[]));
+    scenario.wait(1);
+    assert_eq!(scenario.player().rings(), 1);
+
+    // Leave before the sparkle finishes, then come back
+    scenario.game.player.set_position(Vector2 { x: 2700.0, y: 300.0 });
+    scenario.wait(1);
+    scenario.game.player.set_position(position);
+    scenario.wait(60);
+    assert_eq!(count_kind(&scenario, "RING"), rings);
+    assert_eq!(scenario.player().rings(), 1);
+}
+
+#[test]
+fn entity_flags_are_checked_against_their_schema() {
+    let platform = "10 20 PLATFORM h 32".parse::<Entity>().unwrap();
+    assert_eq!(platform.flags().choice("motion"), "h");
+    assert_eq!(platform.flags().number("distance"), 32.0);
+    assert_eq!(platform.flags().integer("period"), 180);
+
+    // Only the flags that were written get saved
+    assert_eq!(platform.to_string(), "10 20 PLATFORM h 32 ");
+    assert_eq!(platform.to_string().parse::<Entity>().unwrap(), platform);
+
+    let error = "10 20 SPRINGYELLOW sideways".parse::<Entity>().unwrap_err();
+    assert!(error.contains("direction"), "{}", error);
+    assert!("10 20 PLATFORM h far".parse::<Entity>().is_err());
+    assert!("10 20 RING u".parse::<Entity>().is_err());
+    assert!("10 20 PATHSWITCH".parse::<Entity>().is_err());
+
+    // Whole numbers have to be in range too
+    let error = "10 20 BRIDGE 0".parse::<Entity>().unwrap_err();
+    assert!(error.contains("logs"), "{}", error);
+    assert!("10 20 BRIDGE 1000000".parse::<Entity>().is_err());
+    assert!("10 20 PLATFORM h 32 -5".parse::<Entity>().is_err());
+    assert_eq!("10 20 BRIDGE 1".parse::<Entity>().unwrap().flags().integer("logs"), 1);
+
+    // Entities made in code get their flags checked the same way
+    let position = Vector2 { x: 10.0, y: 20.0 };
+    assert!(Entity::new(position, "BRIDGE".to_string(), vec!["0".to_string()]).is_err());
+    assert!(Entity::new(position, "BRIDGE".to_string(), vec!["4".to_string()]).is_ok());
+}
+
+#[test]
+fn unknown_kinds_load_as_placeholders() {
+    let entity = "100 200 SPRINGYELOW u".parse::<Entity>().unwrap();
+    assert!(entity.is_placeholder());
+    assert!(entity.hitbox().is_none());
+    assert_eq!(entity.to_string(), "100 200 SPRINGYELOW u ");
+
+    let mut scenario = clear_ground();
+    let position = scenario.position();
+    scenario.game.entities.push(Entity::new(position, "NOTAKIND".to_string(), Vec::new()).unwrap());
+    scenario.play("hold right 30");
+    assert!(scenario.game.entities.iter().any(|e| e.is_placeholder()));
+    assert!(scenario.position().x > position.x);
+}